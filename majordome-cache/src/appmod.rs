@@ -8,7 +8,7 @@ use std::{
 use async_trait::async_trait;
 use majordome::{
     appmod_decl_self_pointer, AppMod, AppModBuilder, AppModConfigGetter, AppModInitOptions,
-    AppModRuntime, MajordomeError,
+    AppModRuntime, HealthStatus, MajordomeApp, MajordomeError,
 };
 use moka::future::Cache;
 
@@ -34,7 +34,13 @@ pub struct CacheConfig {
     pub max_size: u64,
 }
 
-impl AppModRuntime for MajordomeCache {}
+#[async_trait]
+impl AppModRuntime for MajordomeCache {
+    async fn health(&self, _app: MajordomeApp) -> HealthStatus {
+        let entries = self.response_cache.entry_count();
+        HealthStatus::healthy().details(&format!("{} entries", entries))
+    }
+}
 
 #[async_trait]
 impl AppMod for MajordomeCache {
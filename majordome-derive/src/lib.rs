@@ -7,8 +7,9 @@ mod majordome_scylla;
 /// - `prefix`: Prefix for the error code. Required.
 /// Enum Variants Attributes:
 /// - `code`: Error code. Required.
-/// - `msg`: Error message. The string is formatted using enum variant fields. Required.
-/// - `status`: HTTP status code. Required.
+/// - `msg`: Error message. The string is formatted using enum variant fields, which must all be used. Required.
+/// - `status`: HTTP status code (100-599). Required.
+/// - `header(name = "...", value = "...")`: Response header, the value can use the named fields. Optional, repeatable.
 ///
 /// # Example
 /// ```rs
@@ -23,9 +24,14 @@ mod majordome_scylla;
 ///
 ///     #[err(code="not_enough_players", msg="Not enough players (required: {required}, actual: {actual})", status=400)]
 ///     NotEnoughPlayers{required: u32, actual: u32},
+///
+///     #[err(code="rate_limited", msg="Too many requests, retry in {retry}s", status=429, header(name="Retry-After", value="{retry}"))]
+///     RateLimited{retry: u64},
 /// }
 /// ```
 ///
+/// The variants are listed in `majordome::error_catalog()`, see `ErrorCatalog`.
+///
 /// Into/From are implemented for MajordomeError.
 /// ```rs
 /// AuthError::UnknownEvent{id: "123".to_string()}.into()
@@ -37,8 +43,13 @@ mod majordome_scylla;
 ///     "Unknown event 123".to_string(),
 ///     vec!["123".to_string()],
 ///     404
-/// )
+/// ).with_param("id", &"123")
 /// ```
+/// Named fields are added to `params`, serialized if they implement `Serialize`, as strings otherwise.
+///
+/// `TryFrom<MajordomeError>` maps an error received from another service back to the enum,
+/// the fields are read from `params` or `values` (they need `Deserialize` or `FromStr`).
+/// A variant with a field implementing neither is never mapped back, `try_from` returns the error as is.
 #[proc_macro_derive(IntoMajordomeError, attributes(err))]
 pub fn into_majordome_error_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     majordome_errors::parse_enum_error(input)
@@ -3,164 +3,637 @@ use quote::*;
 use syn::*;
 
 pub(crate) fn parse_enum_error(input: TokenStream) -> TokenStream {
-    let ast: syn::DeriveInput = syn::parse(input).unwrap();
+    let ast = parse_macro_input!(input as DeriveInput);
+    match expand_enum_error(&ast) {
+        Ok(gen) => gen.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
 
-    let mut prefix = String::new();
-    for attr in ast.attrs.iter() {
-        if !attr.path.is_ident("err") {
-            continue;
+/// `#[err(code = "...", msg = "...", status = ..., header(...))]` of a variant.
+struct VariantAttrs {
+    code: String,
+    msg: LitStr,
+    status: u16,
+    headers: Vec<(String, LitStr)>,
+}
+
+fn expand_enum_error(ast: &DeriveInput) -> Result<proc_macro2::TokenStream> {
+    let name = &ast.ident;
+    let variants = match &ast.data {
+        Data::Enum(DataEnum { variants, .. }) => variants,
+        _ => {
+            return Err(Error::new_spanned(
+                name,
+                "IntoMajordomeError can only be derived for enums",
+            ))
         }
+    };
+    let prefix = parse_prefix(&ast.attrs)?;
 
-        let meta = attr.parse_meta().unwrap();
-        match meta {
-            Meta::List(nv) => {
-                for nested_meta in nv.nested {
-                    if let NestedMeta::Meta(Meta::NameValue(nv)) = nested_meta {
-                        match nv.path.get_ident() {
-                            Some(ident) if ident == "prefix" => {
-                                if let Lit::Str(lit_str) = nv.lit {
-                                    prefix = lit_str.value();
-                                }
-                            }
-                            _ => (),
-                        }
+    // every variant is checked, to report all the errors at once.
+    let mut parsed = Vec::new();
+    let mut errors: Option<Error> = None;
+    for variant in variants {
+        match parse_variant_attrs(variant) {
+            Ok(attrs) => parsed.push((variant, attrs)),
+            Err(e) => match &mut errors {
+                Some(errors) => errors.combine(e),
+                None => errors = Some(e),
+            },
+        }
+    }
+    if let Some(e) = errors {
+        return Err(e);
+    }
+
+    let enum_match_arms = parsed.into_iter().map(|(variant, attrs)| {
+        let variant_ident = &variant.ident;
+        let fields = &variant.fields;
+        let VariantAttrs {
+            code,
+            msg,
+            status,
+            headers,
+        } = attrs;
+
+        let code = format!("{}{}", prefix, code);
+        // header values capture the named fields, eg. `{retry}`.
+        let header_names = headers.iter().map(|(name, _)| name);
+        let header_values = headers.iter().map(|(_, value)| value);
+        let with_headers = quote! {
+            #(.with_header(#header_names, &format!(#header_values)))*
+        };
+
+        let field_strs: Vec<String> = match fields {
+            Fields::Named(FieldsNamed { named, .. }) => named
+                .iter()
+                .map(|f| f.ident.as_ref().unwrap().to_string())
+                .collect(),
+            Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => {
+                (0..unnamed.len()).map(|i| i.to_string()).collect()
+            }
+            Fields::Unit => vec![],
+        };
+        let enum_str = name.to_string();
+        let variant_str = variant_ident.to_string();
+        let definition = quote! {
+            ::majordome::ErrorDefinition {
+                code: #code,
+                status: #status,
+                message: #msg,
+                fields: &[#(#field_strs),*],
+                enum_name: #enum_str,
+                variant: #variant_str,
+            }
+        };
+
+        // reads the fields back from the params, or the values, see `TryFrom<MajordomeError>`.
+        let field_types: Vec<&Type> = fields.iter().map(|f| &f.ty).collect();
+        let indexes = 0..field_types.len();
+        let field_reads = quote! {
+            #(
+                (&&&::majordome::__ParamTarget::<#field_types>::new())
+                    .__from_param(&err, #indexes, #field_strs),
+            )*
+        };
+        let try_arm = match fields {
+            Fields::Named(FieldsNamed { named, .. }) => {
+                let field_names = named.iter().map(|f| &f.ident);
+                let field_names2 = field_names.clone();
+                let field_names3 = field_names.clone();
+                quote! {
+                    #code => match (#field_reads) {
+                        (#(Some(#field_names),)*) => Some(#name::#variant_ident { #(#field_names2: #field_names3),* }),
+                        _ => None,
                     }
                 }
             }
-            _ => (),
-        }
-    }
+            Fields::Unit => quote! { #code => Some(#name::#variant_ident) },
+            Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => {
+                let field_names: Vec<Ident> = (0..unnamed.len())
+                    .map(|i| quote::format_ident!("__p{}", i))
+                    .collect();
+                quote! {
+                    #code => match (#field_reads) {
+                        (#(Some(#field_names),)*) => Some(#name::#variant_ident(#(#field_names),*)),
+                        _ => None,
+                    }
+                }
+            }
+        };
 
-    match ast.data {
-        Data::Enum(DataEnum { variants, .. }) => {
-            let name = &ast.ident;
-            let enum_match_arms = variants.into_iter().map(|variant| {
-                let variant_ident = &variant.ident;
-                let attrs = &variant.attrs;
-                let fields = &variant.fields;
-
-                let mut code = String::new();
-                let mut msg = String::new();
-                let mut status: u16 = 0;
-
-                for attr in attrs {
-                    if attr.path.is_ident("err") {
-                        let meta = attr.parse_meta().unwrap();
-                        match meta {
-                            Meta::List(nv) => {
-                                for nested_meta in nv.nested {
-                                    if let NestedMeta::Meta(Meta::NameValue(nv)) = nested_meta {
-                                        match nv.path.get_ident() {
-                                            Some(ident) if ident == "code" => {
-                                                if let Lit::Str(lit_str) = nv.lit {
-                                                    code = lit_str.value();
-                                                }
-                                            }
-                                            Some(ident) if ident == "msg" => {
-                                                if let Lit::Str(lit_str) = nv.lit {
-                                                    msg = lit_str.value();
-                                                }
-                                            }
-                                            Some(ident) if ident == "status" => {
-                                                if let Lit::Int(lit_int) = nv.lit {
-                                                    status = lit_int
-                                                        .base10_parse()
-                                                        .expect("status must be an u16");
-                                                }
-                                            }
-                                            _ => (),
-                                        }
-                                    }
-                                }
-                            }
-                            _ => (),
-                        }
+        let arm = match fields {
+            Fields::Named(FieldsNamed { named, .. }) => {
+                let msg = LitStr::new(&name_positional(&msg.value(), &field_strs), msg.span());
+                let field_names = named.iter().map(|f| &f.ident);
+                let field_names2 = field_names.clone();
+                let field_names3 = field_names.clone();
+                let field_names4 = field_names.clone();
+                let field_strs = &field_strs;
+                quote! {
+                    #name::#variant_ident { #(#field_names),* } => {
+                        #[allow(unused_imports)]
+                        use ::majordome::{__DisplayParam as _, __SerializeParam as _};
+
+                        let mut err = ::majordome::MajordomeError::new(
+                            #code.to_string(),
+                            format!(#msg, #(#field_names2 = #field_names2),*),
+                            vec![#(#field_names3.to_string()),*],
+                            #status
+                        )#with_headers;
+                        #(
+                            err.params.insert(
+                                #field_strs.to_string(),
+                                (&::majordome::__ParamValue(&#field_names4)).__param(),
+                            );
+                        )*
+                        err
+                    }
+                }
+            }
+            Fields::Unit => {
+                quote! {
+                    #name::#variant_ident => {
+                        ::majordome::MajordomeError::new(
+                            #code.to_string(),
+                            #msg.to_string(),
+                            vec![],
+                            #status
+                        )#with_headers
                     }
                 }
+            }
+            Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => {
+                let field_names = unnamed
+                    .iter()
+                    .enumerate()
+                    .map(|(i, _)| quote::format_ident!("__p{}", i));
+                let field_names2 = field_names.clone();
+                let field_names3 = field_names.clone();
+                quote! {
+                    #name::#variant_ident(#(#field_names),*) => {
+                        ::majordome::MajordomeError::new(
+                            #code.to_string(),
+                            format!(#msg, #(#field_names2),*),
+                            vec![#(#field_names3.to_string()),*],
+                            #status
+                        )#with_headers
+                    }
+                }
+            }
+        };
 
-                if code == "" {
-                    panic!("Missing code attribute for variant {}", variant_ident);
+        (arm, (definition, try_arm))
+    });
+    let (enum_match_arms, rest): (Vec<_>, Vec<_>) = enum_match_arms.unzip();
+    let (definitions, try_arms): (Vec<_>, Vec<_>) = rest.into_iter().unzip();
+
+    let gen = quote! {
+        impl From<#name> for ::majordome::MajordomeError {
+            fn from(err: #name) -> ::majordome::MajordomeError {
+                match err {
+                    #(#enum_match_arms),*
                 }
-                if msg == "" {
-                    panic!("Missing msg attribute for variant {}", variant_ident);
+            }
+        }
+
+        impl #name {
+            /// Return Err(MajordomeError) with the current enum variant
+            /// as the error.
+            pub fn err<T>(self) -> Result<T, ::majordome::MajordomeError> {
+                Err(self.into())
+            }
+        }
+
+        impl ::std::convert::TryFrom<::majordome::MajordomeError> for #name {
+            type Error = ::majordome::MajordomeError;
+
+            /// Map an error received from another service back to its variant,
+            /// the error is returned if the code is unknown or a field can't be read.
+            fn try_from(err: ::majordome::MajordomeError) -> Result<Self, Self::Error> {
+                #[allow(unused_imports)]
+                use ::majordome::{__DeserializeParam as _, __FromStrParam as _, __NoParam as _};
+
+                let parsed = match err.error.as_str() {
+                    #(#try_arms,)*
+                    _ => None,
+                };
+                parsed.ok_or(err)
+            }
+        }
+
+        impl ::majordome::ErrorCatalog for #name {
+            fn error_definitions() -> &'static [::majordome::ErrorDefinition] {
+                &[#(#definitions),*]
+            }
+        }
+
+        ::majordome::__submit_error_catalog!(#name);
+    };
+
+    Ok(gen)
+}
+
+/// Items of an `#[err(...)]` attribute.
+fn err_items(attr: &Attribute) -> Result<Vec<NestedMeta>> {
+    match attr.parse_meta()? {
+        Meta::List(list) => Ok(list.nested.into_iter().collect()),
+        meta => Err(Error::new_spanned(meta, "expected #[err(...)]")),
+    }
+}
+
+fn lit_str(nv: &MetaNameValue) -> Result<LitStr> {
+    match &nv.lit {
+        Lit::Str(lit_str) => Ok(lit_str.clone()),
+        lit => Err(Error::new_spanned(
+            lit,
+            format!("`{}` must be a string", nv.path.to_token_stream()),
+        )),
+    }
+}
+
+/// Parse `#[err(prefix = "errors.gg.wls.")]` on the enum.
+fn parse_prefix(attrs: &[Attribute]) -> Result<String> {
+    let mut prefix = String::new();
+    for attr in attrs.iter().filter(|a| a.path.is_ident("err")) {
+        for item in err_items(attr)? {
+            match &item {
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("prefix") => {
+                    prefix = lit_str(nv)?.value();
                 }
-                if status == 0 {
-                    panic!("Missing status attribute for variant {}", variant_ident);
+                _ => {
+                    return Err(Error::new_spanned(
+                        item,
+                        "unknown key in #[err(...)] of the enum, expected `prefix`",
+                    ))
                 }
+            }
+        }
+    }
+    Ok(prefix)
+}
 
-                let code = format!("{}{}", prefix, code);
+fn parse_variant_attrs(variant: &Variant) -> Result<VariantAttrs> {
+    let mut code = None;
+    let mut msg = None;
+    let mut status = None;
+    let mut headers = Vec::new();
 
-                match fields {
-                    Fields::Named(FieldsNamed { named, .. }) => {
-                        let field_names = named.iter().map(|f| &f.ident);
-                        let field_names2 = field_names.clone();
-                        let field_names3 = field_names.clone();
-                        quote! {
-                            #name::#variant_ident { #(#field_names),* } => {
-                                ::majordome::MajordomeError::new(
-                                    #code.to_string(),
-                                    format!(#msg, #(#field_names2 = #field_names2),*),
-                                    vec![#(#field_names3.to_string()),*],
-                                    #status
-                                )
-                            }
-                        }
+    for attr in variant.attrs.iter().filter(|a| a.path.is_ident("err")) {
+        for item in err_items(attr)? {
+            match &item {
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("code") => {
+                    let lit = lit_str(nv)?;
+                    if lit.value().is_empty() {
+                        return Err(Error::new_spanned(lit, "`code` can't be empty"));
                     }
-                    Fields::Unit => {
-                        quote! {
-                            #name::#variant_ident => {
-                                ::majordome::MajordomeError::new(
-                                    #code.to_string(),
-                                    #msg.to_string(),
-                                    vec![],
-                                    #status
-                                )
-                            }
-                        }
-                    }
-                    Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => {
-                        let field_names = unnamed
-                            .iter()
-                            .enumerate()
-                            .map(|(i, _)| quote::format_ident!("__p{}", i));
-                        let field_names2 = field_names.clone();
-                        let field_names3 = field_names.clone();
-                        quote! {
-                            #name::#variant_ident(#(#field_names),*) => {
-                                ::majordome::MajordomeError::new(
-                                    #code.to_string(),
-                                    format!(#msg, #(#field_names2),*),
-                                    vec![#(#field_names3.to_string()),*],
-                                    #status
-                                )
-                            }
+                    code = Some(lit.value());
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("msg") => {
+                    msg = Some(lit_str(nv)?);
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("status") => {
+                    status = Some(parse_status(nv)?);
+                }
+                NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("header") => {
+                    headers.push(parse_header(list)?);
+                }
+                _ => {
+                    return Err(Error::new_spanned(
+                        item,
+                        "unknown key in #[err(...)], expected `code`, `msg`, `status` or `header(name = \"...\", value = \"...\")`",
+                    ))
+                }
+            }
+        }
+    }
+
+    let missing = |key: &str| {
+        Error::new_spanned(
+            &variant.ident,
+            format!(
+                "missing `{}` in #[err(...)] for variant {}",
+                key, variant.ident
+            ),
+        )
+    };
+    let attrs = VariantAttrs {
+        code: code.ok_or_else(|| missing("code"))?,
+        msg: msg.ok_or_else(|| missing("msg"))?,
+        status: status.ok_or_else(|| missing("status"))?,
+        headers,
+    };
+
+    check_placeholders(&variant.fields, &attrs.msg, true)?;
+    for (_, value) in &attrs.headers {
+        check_placeholders(&variant.fields, value, false)?;
+    }
+    Ok(attrs)
+}
+
+fn parse_status(nv: &MetaNameValue) -> Result<u16> {
+    let Lit::Int(lit) = &nv.lit else {
+        return Err(Error::new_spanned(
+            &nv.lit,
+            "`status` must be an integer, eg. `status = 404`",
+        ));
+    };
+
+    let status: u64 = lit.base10_parse()?;
+    if !(100..=599).contains(&status) {
+        return Err(Error::new_spanned(
+            lit,
+            "`status` must be an HTTP status code (100-599)",
+        ));
+    }
+    Ok(status as u16)
+}
+
+/// Parse `header(name = "Retry-After", value = "{retry}")`.
+fn parse_header(list: &MetaList) -> Result<(String, LitStr)> {
+    let mut name = None;
+    let mut value = None;
+    for item in list.nested.iter() {
+        match item {
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("name") => {
+                name = Some(lit_str(nv)?);
+            }
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("value") => {
+                value = Some(lit_str(nv)?);
+            }
+            _ => {
+                return Err(Error::new_spanned(
+                    item,
+                    "unknown key in header(...), expected `name` or `value`",
+                ))
+            }
+        }
+    }
+
+    let (Some(name), Some(value)) = (name, value) else {
+        return Err(Error::new_spanned(
+            list,
+            "header(...) requires a `name` and a `value`",
+        ));
+    };
+    let valid = !name.value().is_empty()
+        && name
+            .value()
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c));
+    if !valid {
+        return Err(Error::new_spanned(name, "invalid header name"));
+    }
+    Ok((name.value(), value))
+}
+
+enum Placeholder {
+    Next,
+    Index(usize),
+    Name(String),
+}
+
+/// Placeholders of a format string, `{{` and `}}` are escaped braces.
+fn placeholders(s: &str) -> std::result::Result<Vec<Placeholder>, String> {
+    let mut placeholders = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+            }
+            '}' => return Err("unmatched `}`, use `}}` for a literal brace".to_string()),
+            '{' => {
+                let mut inner = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => inner.push(c),
+                        None => {
+                            return Err("unclosed `{`, use `{{` for a literal brace".to_string())
                         }
                     }
                 }
-            });
 
-            let gen = quote! {
-                impl From<#name> for ::majordome::MajordomeError {
-                    fn from(err: #name) -> ::majordome::MajordomeError {
-                        match err {
-                            #(#enum_match_arms),*
-                        }
+                let arg = inner.split(':').next().unwrap_or_default().trim();
+                placeholders.push(match arg.parse::<usize>() {
+                    _ if arg.is_empty() => Placeholder::Next,
+                    Ok(index) => Placeholder::Index(index),
+                    Err(_) => Placeholder::Name(arg.to_string()),
+                });
+            }
+            _ => (),
+        }
+    }
+    Ok(placeholders)
+}
+
+/// Replace the positional placeholders by the field names, eg. `{0}` by `{id}`,
+/// named arguments used by position are linted by rustc.
+fn name_positional(s: &str, names: &[String]) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    let mut next = 0;
+    while let Some(c) = chars.next() {
+        out.push(c);
+        match c {
+            '{' | '}' if chars.peek() == Some(&c) => out.push(chars.next().unwrap()),
+            '{' => {
+                let mut inner = String::new();
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        break;
                     }
+                    inner.push(c);
                 }
 
-                impl #name {
-                    /// Return Err(MajordomeError) with the current enum variant
-                    /// as the error.
-                    pub fn err<T>(self) -> Result<T, ::majordome::MajordomeError> {
-                        Err(self.into())
+                let (arg, spec) = match inner.split_once(':') {
+                    Some((arg, spec)) => (arg.trim(), Some(spec)),
+                    None => (inner.trim(), None),
+                };
+                let index = match arg.parse::<usize>() {
+                    _ if arg.is_empty() => {
+                        next += 1;
+                        Some(next - 1)
                     }
+                    Ok(index) => Some(index),
+                    Err(_) => None,
+                };
+                out.push_str(index.and_then(|i| names.get(i)).map_or(arg, |n| n.as_str()));
+                if let Some(spec) = spec {
+                    out.push(':');
+                    out.push_str(spec);
+                }
+                out.push('}');
+            }
+            _ => (),
+        }
+    }
+    out
+}
+
+/// Check the placeholders of a message against the fields of the variant.
+/// The msg gets every field as argument (they must all be used), named fields can also be used by position,
+/// header values only capture the named fields.
+fn check_placeholders(fields: &Fields, lit: &LitStr, is_msg: bool) -> Result<()> {
+    let placeholders = placeholders(&lit.value()).map_err(|e| Error::new_spanned(lit, e))?;
+
+    let names: Vec<String> = fields
+        .iter()
+        .filter_map(|f| f.ident.as_ref().map(|i| i.to_string()))
+        .collect();
+    let positional = match is_msg {
+        true => fields.len(),
+        false => 0,
+    };
+
+    let mut used_names = Vec::new();
+    let mut used_indexes = Vec::new();
+    let mut next = 0;
+    for placeholder in placeholders {
+        let index = match placeholder {
+            Placeholder::Name(name) => {
+                if !names.contains(&name) {
+                    let message = match names.is_empty() {
+                        true => {
+                            format!("unknown field `{}`, the variant has no named fields", name)
+                        }
+                        false => format!(
+                            "unknown field `{}`, expected one of: {}",
+                            name,
+                            names.join(", ")
+                        ),
+                    };
+                    return Err(Error::new_spanned(lit, message));
                 }
+                used_names.push(name);
+                continue;
+            }
+            Placeholder::Next => {
+                next += 1;
+                next - 1
+            }
+            Placeholder::Index(index) => index,
+        };
+
+        if index >= positional {
+            let message = match positional {
+                0 => "positional placeholders can only be used in msg of variants with fields"
+                    .to_string(),
+                n => format!(
+                    "placeholder {{{}}} is out of range, the variant has {} fields",
+                    index, n
+                ),
             };
+            return Err(Error::new_spanned(lit, message));
+        }
+        match names.get(index) {
+            Some(name) => used_names.push(name.clone()),
+            None => used_indexes.push(index),
+        }
+    }
 
-            gen.into()
+    // format! fails on unused arguments.
+    if is_msg && !matches!(fields, Fields::Unit) {
+        if let Some(name) = names.iter().find(|n| !used_names.contains(n)) {
+            return Err(Error::new_spanned(
+                lit,
+                format!("field `{}` is not used in msg", name),
+            ));
         }
-        _ => {
-            panic!("Attribute only applicable for enums")
+        let unnamed = match fields {
+            Fields::Unnamed(_) => positional,
+            _ => 0,
+        };
+        if let Some(index) = (0..unnamed).find(|i| !used_indexes.contains(i)) {
+            return Err(Error::new_spanned(
+                lit,
+                format!("field {} is not used in msg", index),
+            ));
         }
     }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_err(ast: DeriveInput) -> String {
+        expand_enum_error(&ast).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        assert!(expand_enum_error(&parse_quote! {
+            #[err(prefix = "errors.test.")]
+            enum TestError {
+                #[err(code = "a", msg = "Not enough players ({required}/{actual}), {{ok}}", status = 400)]
+                A { required: u32, actual: u32 },
+                #[err(code = "b", msg = "{1} {}", status = 429, header(name = "Retry-After", value = "30"))]
+                B(u32, u32),
+                #[err(code = "c", msg = "Invalid {0} for {user}", status = 400)]
+                C { value: u32, user: String },
+            }
+        })
+        .is_ok());
+
+        let err = expand_err(parse_quote! {
+            enum TestError {
+                #[err(code = "a", msg = "Unknown event {event}", status = 404)]
+                A { id: String },
+            }
+        });
+        assert_eq!(err, "unknown field `event`, expected one of: id");
+
+        let names = ["id".to_string(), "score".to_string()];
+        assert_eq!(
+            name_positional("{0} {{1}} {:>4} {id:?} {1}", &names),
+            "{id} {{1}} {id:>4} {id:?} {score}"
+        );
+
+        let err = expand_err(parse_quote! {
+            enum TestError {
+                #[err(code = "a", msg = "Invalid {1}", status = 400)]
+                A { value: u32, user: String },
+            }
+        });
+        assert_eq!(err, "field `value` is not used in msg");
+
+        let err = expand_err(parse_quote! {
+            enum TestError {
+                #[err(code = "a", msg = "Invalid", status = 700)]
+                A,
+            }
+        });
+        assert_eq!(err, "`status` must be an HTTP status code (100-599)");
+
+        let err = expand_err(parse_quote! {
+            enum TestError {
+                #[err(code = "a", mgs = "Invalid", status = 400)]
+                A,
+            }
+        });
+        assert!(err.starts_with("unknown key in #[err(...)]"));
+
+        let err = expand_err(parse_quote! {
+            enum TestError {
+                #[err(code = "a", msg = "Invalid {}", status = 400)]
+                A(u32, u32),
+            }
+        });
+        assert_eq!(err, "field 1 is not used in msg");
+
+        let err = expand_err(parse_quote! {
+            enum TestError {
+                #[err(code = "a", status = 400)]
+                A,
+            }
+        });
+        assert_eq!(err, "missing `msg` in #[err(...)] for variant A");
+    }
 }
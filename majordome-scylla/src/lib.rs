@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use majordome::{
     appmod_decl_self_pointer, AppMod, AppModBuilder, AppModConfigGetter, AppModInitOptions,
-    AppModRuntime, MajordomeError,
+    AppModRuntime, CommaList, HealthStatus, MajordomeApp, MajordomeError,
 };
 use scylla::{prepared_statement::PreparedStatement, serialize::row::SerializeRow};
 use std::sync::Arc;
@@ -41,7 +41,21 @@ pub struct ScyllaAuth {
     pub password: String,
 }
 
-impl AppModRuntime for ScyllaDB {}
+#[async_trait]
+impl AppModRuntime for ScyllaDB {
+    async fn health(&self, _app: MajordomeApp) -> HealthStatus {
+        let start = std::time::Instant::now();
+        match self
+            .inner
+            .db
+            .query("SELECT now() FROM system.local", ())
+            .await
+        {
+            Ok(_) => HealthStatus::healthy().latency(start.elapsed()),
+            Err(e) => HealthStatus::unhealthy(&e.to_string()).latency(start.elapsed()),
+        }
+    }
+}
 
 #[async_trait]
 impl AppMod for ScyllaDB {
@@ -55,11 +69,7 @@ impl AppMod for ScyllaDB {
     ) -> Result<Self::ModConfig, MajordomeError> {
         let mut c = AppModConfigGetter::new(&opts, builder, "db.scylla");
 
-        let hosts = c
-            .get_or_panic::<String>("hosts")
-            .split(',')
-            .map(|s| s.to_string())
-            .collect();
+        let hosts = c.get_or_panic::<CommaList<String>>("hosts").into_inner();
         let keyspace = c.get_or_panic("keyspace");
 
         let auth = match (c.get_optional("username"), c.get_optional("password")) {
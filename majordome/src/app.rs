@@ -1,18 +1,25 @@
 use std::collections::HashMap;
 use std::ops::Deref;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
+use crate::settings::MajordomeSettings;
 use crate::signal::MajordomeSignal;
-use crate::{AppModBuilder, ModuleStore};
+use crate::{AppModBuilder, ConfigSource, ConfigStore, ModuleStore};
 
 pub struct MajordomeAppInner {
-    // Configuration values, gathered from the environment.
+    // Configuration values, gathered from the configuration file, the environment and the providers.
+    // Modules read their values from here, see `config_value` for reloaded values.
     pub config: HashMap<String, String>,
 
+    // Provenance of the configuration values, and live values once the app is built.
+    pub(crate) config_store: RwLock<ConfigStore>,
+
     // Modules store.
     pub(crate) modules: ModuleStore,
 
-    pub(crate) signal: MajordomeSignal,
+    pub(crate) signal: Arc<MajordomeSignal>,
+
+    pub(crate) settings: MajordomeSettings,
 }
 
 #[derive(Clone)]
@@ -40,34 +47,106 @@ impl Deref for MajordomeApp {
     }
 }
 
-fn get_config() -> HashMap<String, String> {
-    let mut m = HashMap::new();
+pub(crate) fn get_config() -> ConfigStore {
+    get_config_from(get_config_file_path())
+}
+
+/// Load the configuration file at `file`, if any, then the env.
+pub(crate) fn get_config_from(file: Option<String>) -> ConfigStore {
+    let mut store = ConfigStore::default();
+
+    if let Some(path) = file {
+        match std::fs::read_to_string(&path) {
+            Ok(content) => {
+                let entries = parse_config_file(&content);
+                println!(
+                    "✅ Loaded {} configuration entries from '{}'.",
+                    entries.len(),
+                    path
+                );
+                for (k, v) in entries {
+                    store.insert(k, v, ConfigSource::File);
+                }
+            }
+            Err(e) => eprintln!("Failed to read configuration file '{}': {}", path, e),
+        }
+        store.file = Some(path);
+    }
+
+    let mut env_count = 0;
     for (k, v) in std::env::vars() {
-        m.insert(k, v);
+        store.insert(k, v, ConfigSource::Env);
+        env_count += 1;
     }
 
-    println!("✅ Loaded {} configuration entries from env.", m.len());
-    m
+    println!("✅ Loaded {} configuration entries from env.", env_count);
+    store
+}
+
+/// The configuration file is given by `--majordome-config-file=<path>` or `MAJORDOME_CONFIG_FILE`.
+/// Env values take precedence over the file.
+fn get_config_file_path() -> Option<String> {
+    for arg in std::env::args() {
+        if let Some(path) = arg.strip_prefix("--majordome-config-file=") {
+            return Some(path.to_string());
+        }
+    }
+
+    std::env::var("MAJORDOME_CONFIG_FILE").ok()
+}
+
+/// Parse `KEY=VALUE` lines, the format written by `--majordome-dump-env`.
+/// Empty lines and `#` comments are skipped, values may be quoted.
+pub(crate) fn parse_config_file(content: &str) -> Vec<(String, String)> {
+    let mut entries = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let Some((k, v)) = line.split_once('=') else {
+            eprintln!("Ignoring invalid configuration line '{}'.", line);
+            continue;
+        };
+
+        let v = v.trim();
+        let v = match (v.strip_prefix('"'), v.strip_prefix('\'')) {
+            (Some(q), _) => q.strip_suffix('"').unwrap_or(q),
+            (_, Some(q)) => q.strip_suffix('\'').unwrap_or(q),
+            _ => v,
+        };
+        entries.push((k.trim().to_string(), v.to_string()));
+    }
+
+    entries
 }
 
 impl MajordomeApp {
     pub async fn new() -> MajordomeApp {
+        let mut bld = Self::builder().await;
+        bld.app.settings = MajordomeSettings::load(&mut bld);
+        bld.app.config_store.get_mut().unwrap().values = bld.app.config.clone();
+
         let a = MajordomeApp {
-            inner: Arc::new(Self::init().await),
+            inner: Arc::new(bld.app),
         };
         a._start_exiting_probe();
+        a.signal.mark_started();
 
         a
     }
 
     pub(crate) async fn init() -> MajordomeAppInner {
-        let config = get_config();
-        let signal = MajordomeSignal::new();
+        let config_store = get_config();
+        let signal = Arc::new(MajordomeSignal::new());
 
         MajordomeAppInner {
-            config,
+            config: config_store.values.clone(),
+            config_store: RwLock::new(config_store),
             modules: ModuleStore::default(),
             signal,
+            settings: MajordomeSettings::default(),
         }
     }
 
@@ -78,6 +157,27 @@ impl MajordomeApp {
             loaded: HashMap::new(),
             loaded_targets_count: 0,
             env_entries: Vec::new(),
+            config_prefixes: Vec::new(),
+            strict_config: None,
+            capture_panics: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_parse_config_file() {
+        let entries = super::parse_config_file(
+            "# comment\nDB.SCYLLA_HOSTS=a:9042,b:9042\n\n  KEY = \"quoted value\" \nBROKEN\nEMPTY=\n",
+        );
+        assert_eq!(
+            entries,
+            vec![
+                ("DB.SCYLLA_HOSTS".to_string(), "a:9042,b:9042".to_string()),
+                ("KEY".to_string(), "quoted value".to_string()),
+                ("EMPTY".to_string(), "".to_string()),
+            ]
+        );
+    }
+}
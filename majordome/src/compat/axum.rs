@@ -2,15 +2,196 @@ use crate::MajordomeError;
 use axum::{extract::rejection::JsonRejection, extract::FromRequest, response::IntoResponse};
 use serde::Serialize;
 use aide::OperationOutput;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
 
 /// Used for custom error rejections.
 pub struct _MajordomeRejectionError(MajordomeError);
 
+// Rendered without the request: `negotiate_errors` renders it again for the Accept headers.
 impl axum::response::IntoResponse for MajordomeError {
     fn into_response(self) -> axum::http::Response<axum::body::Body> {
-        let status = axum::http::StatusCode::from_u16(self.status_code).unwrap();
-        (status, Json(self)).into_response()
+        let mut resp = match error_format() {
+            ErrorFormat::Majordome => (status_code(&self), Json(self.clone())).into_response(),
+            ErrorFormat::Problem => problem_response(&self, None),
+        };
+        append_headers(&self, resp.headers_mut());
+
+        // kept for negotiate_errors.
+        resp.extensions_mut().insert(RenderedError(self));
+        resp
+    }
+}
+
+fn status_code(err: &MajordomeError) -> axum::http::StatusCode {
+    axum::http::StatusCode::from_u16(err.http_status())
+        .unwrap_or(axum::http::StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+fn append_headers(err: &MajordomeError, headers: &mut axum::http::HeaderMap) {
+    for (name, value) in err.headers.iter() {
+        match (
+            axum::http::HeaderName::try_from(name.as_str()),
+            axum::http::HeaderValue::try_from(value.as_str()),
+        ) {
+            (Ok(name), Ok(value)) => {
+                headers.append(name, value);
+            }
+            _ => eprintln!("Invalid header on error {}: {}: {}", err.error, name, value),
+        }
+    }
+}
+
+/// Body of the error responses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorFormat {
+    /// `{"error": ..., "message": ..., "values": [...]}`, the default.
+    Majordome,
+    /// RFC 7807 `application/problem+json`, see `ProblemDetails`.
+    Problem,
+}
+
+static PROBLEM_FORMAT: AtomicBool = AtomicBool::new(false);
+static PROBLEM_TYPE_BASE: RwLock<String> = RwLock::new(String::new());
+
+/// Set the format of all the error responses.
+/// Use `negotiate_errors` to let the clients ask for problem details instead.
+pub fn set_error_format(format: ErrorFormat) {
+    PROBLEM_FORMAT.store(format == ErrorFormat::Problem, Ordering::Relaxed);
+}
+
+pub fn error_format() -> ErrorFormat {
+    match PROBLEM_FORMAT.load(Ordering::Relaxed) {
+        true => ErrorFormat::Problem,
+        false => ErrorFormat::Majordome,
+    }
+}
+
+/// Prefix of the problem `type`, eg. `https://docs.example.com/errors/`.
+/// By default the type is the error code.
+pub fn set_problem_type_base(base: &str) {
+    *PROBLEM_TYPE_BASE.write().unwrap() = base.to_string();
+}
+
+/// RFC 7807 problem details of a `MajordomeError`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    // extension members.
+    pub code: String,
+    pub values: Vec<String>,
+    #[serde(skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub params: std::collections::BTreeMap<String, serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_id: Option<uuid::Uuid>,
+}
+
+impl MajordomeError {
+    /// Problem details of this error, instance is the request path.
+    pub fn to_problem(&self, instance: Option<&str>) -> ProblemDetails {
+        let status = status_code(self);
+
+        ProblemDetails {
+            type_: format!("{}{}", PROBLEM_TYPE_BASE.read().unwrap(), self.error),
+            title: status.canonical_reason().unwrap_or("Error").to_string(),
+            status: status.as_u16(),
+            detail: self.message.clone(),
+            instance: instance.map(|i| i.to_string()),
+            code: self.error.clone(),
+            values: self.values.clone(),
+            params: (*self.params).clone(),
+            error_id: self.error_id.as_deref().copied(),
+        }
+    }
+}
+
+fn problem_response(err: &MajordomeError, instance: Option<&str>) -> axum::response::Response {
+    let status = status_code(err);
+    let body = serde_json::to_vec(&err.to_problem(instance)).unwrap_or_default();
+
+    (
+        status,
+        [(axum::http::header::CONTENT_TYPE, "application/problem+json")],
+        body,
+    )
+        .into_response()
+}
+
+#[derive(Clone)]
+struct RenderedError(MajordomeError);
+
+/// Whether the Accept header asks for `application/problem+json`.
+fn accepts_problem(headers: &axum::http::HeaderMap) -> bool {
+    headers
+        .get_all(axum::http::header::ACCEPT)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|range| {
+            let mut params = range.split(';').map(str::trim);
+            let media = params.next().unwrap_or_default();
+            let refused = params.any(|p| matches!(p, "q=0" | "q=0.0" | "q=0.00" | "q=0.000"));
+            media.eq_ignore_ascii_case("application/problem+json") && !refused
+        })
+}
+
+/// Middleware rendering the errors as problem details when the client accepts `application/problem+json`
+/// (or when set globally), with the request path as `instance`.
+/// Messages are translated for the `Accept-Language` header, see `add_error_catalog`:
+/// this layer is required for translated error responses.
+/// ```rs
+/// let router = Router::new()
+///     .route("/users/{id}", get(get_user))
+///     .layer(axum::middleware::from_fn(majordome::axum::negotiate_errors));
+/// ```
+pub async fn negotiate_errors(
+    req: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    let problem = accepts_problem(req.headers()) || error_format() == ErrorFormat::Problem;
+    let path = req.uri().path().to_string();
+    let locales = req
+        .headers()
+        .get(axum::http::header::ACCEPT_LANGUAGE)
+        .and_then(|v| v.to_str().ok())
+        .map(crate::parse_accept_language)
+        .unwrap_or_default();
+
+    let mut resp = next.run(req).await;
+    let Some(RenderedError(mut err)) = resp.extensions_mut().remove::<RenderedError>() else {
+        return resp;
+    };
+    let localized = err.localized_message(&locales);
+    if !problem && localized.is_none() {
+        return resp;
+    }
+
+    let language = localized.map(|(locale, message)| {
+        err.message = message;
+        locale
+    });
+    let rendered = match problem {
+        true => problem_response(&err, Some(&path)),
+        false => (status_code(&err), Json(err)).into_response(),
+    };
+
+    // keep the headers set by the handler.
+    let (mut parts, _) = resp.into_parts();
+    let (rendered_parts, body) = rendered.into_parts();
+    parts.headers.remove(axum::http::header::CONTENT_LENGTH);
+    parts.headers.extend(rendered_parts.headers);
+    if let Some(value) = language.and_then(|l| axum::http::HeaderValue::from_str(&l).ok()) {
+        parts
+            .headers
+            .insert(axum::http::header::CONTENT_LANGUAGE, value);
     }
+    axum::response::Response::from_parts(parts, body)
 }
 
 // impl IntoApiResponse for MajordomeError {}
@@ -40,36 +221,36 @@ impl<T: Serialize> IntoResponse for Json<T> {
 impl From<JsonRejection> for _MajordomeRejectionError {
     fn from(rejection: JsonRejection) -> Self {
         let e = match rejection {
-            JsonRejection::BytesRejection(e) => MajordomeError {
-                error: format!("errors.http.bad_request.json.bytes_rejection"),
-                message: e.body_text(),
-                values: vec![e.body_text()],
-                status_code: 400,
-            },
-            JsonRejection::JsonDataError(e) => MajordomeError {
-                error: format!("errors.http.bad_request.json.data_error"),
-                message: e.body_text(),
-                values: vec![e.body_text()],
-                status_code: 400,
-            },
-            JsonRejection::JsonSyntaxError(e) => MajordomeError {
-                error: format!("errors.http.bad_request.json.syntax_error"),
-                message: e.body_text(),
-                values: vec![e.body_text()],
-                status_code: 400,
-            },
-            JsonRejection::MissingJsonContentType(_e) => MajordomeError {
-                error: format!("errors.http.bad_request.json.missing_content_type"),
-                message: format!("Expected request with `Content-Type: application/json`"),
-                values: vec![],
-                status_code: 415,
-            },
-            _ => MajordomeError {
-                error: format!("errors.http.bad_request.json.unknown"),
-                message: format!("Unknown JSON error: {}", rejection.body_text()),
-                values: vec![rejection.body_text()],
-                status_code: 400,
-            },
+            JsonRejection::BytesRejection(e) => MajordomeError::new(
+                format!("errors.http.bad_request.json.bytes_rejection"),
+                e.body_text(),
+                vec![e.body_text()],
+                400,
+            ),
+            JsonRejection::JsonDataError(e) => MajordomeError::new(
+                format!("errors.http.bad_request.json.data_error"),
+                e.body_text(),
+                vec![e.body_text()],
+                400,
+            ),
+            JsonRejection::JsonSyntaxError(e) => MajordomeError::new(
+                format!("errors.http.bad_request.json.syntax_error"),
+                e.body_text(),
+                vec![e.body_text()],
+                400,
+            ),
+            JsonRejection::MissingJsonContentType(_e) => MajordomeError::new(
+                format!("errors.http.bad_request.json.missing_content_type"),
+                format!("Expected request with `Content-Type: application/json`"),
+                vec![],
+                415,
+            ),
+            _ => MajordomeError::new(
+                format!("errors.http.bad_request.json.unknown"),
+                format!("Unknown JSON error: {}", rejection.body_text()),
+                vec![rejection.body_text()],
+                400,
+            ),
         };
         Self(e)
     }
@@ -86,3 +267,130 @@ impl<T: Serialize> IntoResponse for Form<T> {
         axum::Json(value).into_response()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request, routing::get, Router};
+    use tower::ServiceExt;
+
+    // Held by the tests depending on the process-wide error format.
+    static FORMAT: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+    async fn not_found() -> Result<(), MajordomeError> {
+        Err(MajordomeError::new(
+            "errors.users.not_found".to_string(),
+            "User 42 not found".to_string(),
+            vec!["42".to_string()],
+            404,
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_error_headers() {
+        let _format = FORMAT.lock().await;
+        let err = MajordomeError::new(
+            "errors.test.rate_limited".to_string(),
+            "Too many requests".to_string(),
+            vec![],
+            429,
+        )
+        .with_header("Retry-After", "30")
+        .with_header("Invalid Header", "x");
+        let resp = err.into_response();
+        assert_eq!(resp.status(), 429);
+        assert_eq!(resp.headers()["retry-after"], "30");
+        assert_eq!(resp.headers().len(), 2);
+
+        let err = MajordomeError::new("errors.test".to_string(), "test".to_string(), vec![], 1000);
+        assert_eq!(err.into_response().status(), 500);
+    }
+
+    async fn call(accept: &str) -> (String, serde_json::Value) {
+        call_with(accept, "").await
+    }
+
+    async fn call_with(accept: &str, language: &str) -> (String, serde_json::Value) {
+        let router = Router::new()
+            .route("/users/42", get(not_found))
+            .layer(axum::middleware::from_fn(negotiate_errors));
+
+        let req = Request::get("/users/42")
+            .header("accept", accept)
+            .header("accept-language", language)
+            .body(Body::empty())
+            .unwrap();
+        let resp = router.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), 404);
+
+        let content_type = resp.headers()["content-type"].to_str().unwrap().to_string();
+        let body = axum::body::to_bytes(resp.into_body(), 1 << 16)
+            .await
+            .unwrap();
+        (content_type, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_problem_negotiation() {
+        let _format = FORMAT.lock().await;
+        let (content_type, body) = call("application/json").await;
+        assert_eq!(content_type, "application/json");
+        assert_eq!(body["error"], "errors.users.not_found");
+
+        let (content_type, body) = call("application/problem+json, application/json;q=0.5").await;
+        assert_eq!(content_type, "application/problem+json");
+        assert_eq!(body["type"], "errors.users.not_found");
+        assert_eq!(body["title"], "Not Found");
+        assert_eq!(body["status"], 404);
+        assert_eq!(body["detail"], "User 42 not found");
+        assert_eq!(body["instance"], "/users/42");
+        assert_eq!(body["values"][0], "42");
+
+        let (content_type, _) = call("application/problem+json;q=0").await;
+        assert_eq!(content_type, "application/json");
+
+        let err = MajordomeError::new("errors.test".to_string(), "test".to_string(), vec![], 1000);
+        assert_eq!(err.to_problem(None).status, 500);
+        assert_eq!(problem_response(&err, None).status(), 500);
+    }
+
+    #[tokio::test]
+    async fn test_problem_format() {
+        let _format = FORMAT.lock().await;
+        set_error_format(ErrorFormat::Problem);
+        let resp = not_found().await.unwrap_err().into_response();
+        set_error_format(ErrorFormat::Majordome);
+
+        assert_eq!(resp.status(), 404);
+        assert_eq!(resp.headers()["content-type"], "application/problem+json");
+        let body = axum::body::to_bytes(resp.into_body(), 1 << 16)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["type"], "errors.users.not_found");
+        assert_eq!(body["title"], "Not Found");
+        assert_eq!(body["status"], 404);
+        assert_eq!(body["detail"], "User 42 not found");
+
+        let resp = not_found().await.unwrap_err().into_response();
+        assert_eq!(resp.headers()["content-type"], "application/json");
+    }
+
+    #[tokio::test]
+    async fn test_localized_errors() {
+        let _format = FORMAT.lock().await;
+        crate::add_error_catalog(
+            "fr",
+            [("errors.users.not_found", "Utilisateur {0} introuvable")],
+        );
+
+        let (_, body) = call_with("application/json", "fr-FR, en;q=0.5").await;
+        assert_eq!(body["message"], "Utilisateur 42 introuvable");
+
+        let (_, body) = call_with("application/problem+json", "fr").await;
+        assert_eq!(body["detail"], "Utilisateur 42 introuvable");
+
+        let (_, body) = call_with("application/json", "es").await;
+        assert_eq!(body["message"], "User 42 not found");
+    }
+}
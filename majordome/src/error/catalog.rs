@@ -0,0 +1,263 @@
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+use serde::Serialize;
+
+use crate::AppModBuilder;
+
+/// An error a service can return, generated by `#[derive(IntoMajordomeError)]` for each variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ErrorDefinition {
+    pub code: &'static str,
+    pub status: u16,
+    // The `msg` template, eg. `Unknown event {id}`.
+    pub message: &'static str,
+    pub fields: &'static [&'static str],
+    #[serde(rename = "enum")]
+    pub enum_name: &'static str,
+    pub variant: &'static str,
+}
+
+/// Errors of an enum, implemented by `#[derive(IntoMajordomeError)]`.
+pub trait ErrorCatalog {
+    fn error_definitions() -> &'static [ErrorDefinition];
+}
+
+type DefinitionsFn = fn() -> &'static [ErrorDefinition];
+
+/// Entry of the distributed registry, submitted by the derive with the `catalog` feature.
+#[doc(hidden)]
+pub struct ErrorCatalogEntry {
+    #[cfg_attr(not(feature = "catalog"), allow(dead_code))]
+    definitions: DefinitionsFn,
+}
+
+impl ErrorCatalogEntry {
+    pub const fn new(definitions: DefinitionsFn) -> Self {
+        ErrorCatalogEntry { definitions }
+    }
+}
+
+#[cfg(feature = "catalog")]
+inventory::collect!(ErrorCatalogEntry);
+
+#[cfg(feature = "catalog")]
+#[doc(hidden)]
+pub use inventory as __inventory;
+
+/// Used by the derive, registers the errors of an enum when the `catalog` feature is enabled.
+#[cfg(feature = "catalog")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __submit_error_catalog {
+    ($ty:ty) => {
+        $crate::__inventory::submit! {
+            $crate::ErrorCatalogEntry::new(<$ty as $crate::ErrorCatalog>::error_definitions)
+        }
+    };
+}
+
+#[cfg(not(feature = "catalog"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __submit_error_catalog {
+    ($ty:ty) => {};
+}
+
+// enum type name -> definitions, registered with `register_error_catalog`.
+static REGISTERED: RwLock<BTreeMap<&'static str, DefinitionsFn>> = RwLock::new(BTreeMap::new());
+
+/// Register the errors of an enum, not needed with the `catalog` feature.
+pub fn register_error_catalog<E: ErrorCatalog + 'static>() {
+    REGISTERED
+        .write()
+        .unwrap()
+        .insert(std::any::type_name::<E>(), E::error_definitions);
+}
+
+impl AppModBuilder {
+    /// Register the errors of an enum, see `register_error_catalog`.
+    pub fn error_catalog<E: ErrorCatalog + 'static>(self) -> Self {
+        register_error_catalog::<E>();
+        self
+    }
+}
+
+fn catalogs() -> Vec<DefinitionsFn> {
+    #[allow(unused_mut)]
+    let mut catalogs: Vec<DefinitionsFn> = REGISTERED.read().unwrap().values().copied().collect();
+
+    #[cfg(feature = "catalog")]
+    catalogs.extend(
+        inventory::iter::<ErrorCatalogEntry>
+            .into_iter()
+            .map(|e| e.definitions),
+    );
+
+    catalogs
+}
+
+/// Every error code the service can return, sorted by code.
+/// Enums deriving `IntoMajordomeError` are listed with the `catalog` feature,
+/// or once registered with `register_error_catalog`.
+pub fn error_catalog() -> Vec<ErrorDefinition> {
+    let mut definitions: Vec<ErrorDefinition> = catalogs()
+        .into_iter()
+        .flat_map(|definitions| definitions().iter().copied())
+        .collect();
+
+    // an enum can be both registered and submitted.
+    definitions.sort_by_key(|d| (d.code, d.enum_name, d.variant));
+    definitions.dedup();
+    definitions
+}
+
+/// The error catalog as a JSON array, eg. for the API docs.
+pub fn error_catalog_json() -> String {
+    serde_json::to_string_pretty(&error_catalog()).unwrap()
+}
+
+/// The error codes as a TypeScript union type, with their status and fields.
+/// ```ts
+/// export type ErrorCode =
+///   | "errors.gg.wls.invalid_token"
+///   | "errors.gg.wls.unknown_event";
+///
+/// export const ERROR_STATUS: Record<ErrorCode, number> = { ... };
+/// export const ERROR_FIELDS: Record<ErrorCode, string[]> = { ... };
+/// ```
+pub fn error_catalog_typescript() -> String {
+    let mut definitions = error_catalog();
+    definitions.dedup_by_key(|d| d.code);
+
+    let mut ts = String::from("export type ErrorCode =");
+    if definitions.is_empty() {
+        ts.push_str(" never");
+    }
+    for d in &definitions {
+        ts.push_str(&format!("\n  | {}", json_str(d.code)));
+    }
+    ts.push_str(";\n\nexport const ERROR_STATUS: Record<ErrorCode, number> = {\n");
+    for d in &definitions {
+        ts.push_str(&format!("  {}: {},\n", json_str(d.code), d.status));
+    }
+    ts.push_str("};\n\nexport const ERROR_FIELDS: Record<ErrorCode, string[]> = {\n");
+    for d in &definitions {
+        let fields: Vec<String> = d.fields.iter().map(|f| json_str(f)).collect();
+        ts.push_str(&format!(
+            "  {}: [{}],\n",
+            json_str(d.code),
+            fields.join(", ")
+        ));
+    }
+    ts.push_str("};\n");
+    ts
+}
+
+fn json_str(s: &str) -> String {
+    serde_json::to_string(s).unwrap()
+}
+
+/// Codes defined by more than one variant, with the `Enum::Variant` defining them.
+pub fn duplicate_error_codes() -> Vec<(String, Vec<String>)> {
+    let mut variants: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for d in error_catalog() {
+        variants
+            .entry(d.code)
+            .or_default()
+            .push(format!("{}::{}", d.enum_name, d.variant));
+    }
+
+    variants
+        .into_iter()
+        .filter(|(_, v)| v.len() > 1)
+        .map(|(code, v)| (code.to_string(), v))
+        .collect()
+}
+
+/// Test helper, panics if an error code is defined twice.
+/// ```rs
+/// #[test]
+/// fn test_error_codes() {
+///     majordome::assert_unique_error_codes();
+/// }
+/// ```
+pub fn assert_unique_error_codes() {
+    let duplicates = duplicate_error_codes();
+    if duplicates.is_empty() {
+        return;
+    }
+
+    let lines: Vec<String> = duplicates
+        .iter()
+        .map(|(code, variants)| format!("- {}: {}", code, variants.join(", ")))
+        .collect();
+    panic!("Duplicate error codes:\n{}", lines.join("\n"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct UserError;
+    struct BillingError;
+
+    impl ErrorCatalog for UserError {
+        fn error_definitions() -> &'static [ErrorDefinition] {
+            &[ErrorDefinition {
+                code: "errors.test.catalog.not_found",
+                status: 404,
+                message: "User {id} not found",
+                fields: &["id"],
+                enum_name: "UserError",
+                variant: "NotFound",
+            }]
+        }
+    }
+
+    impl ErrorCatalog for BillingError {
+        fn error_definitions() -> &'static [ErrorDefinition] {
+            &[ErrorDefinition {
+                code: "errors.test.catalog.not_found",
+                status: 404,
+                message: "Invoice {0} not found",
+                fields: &["0"],
+                enum_name: "BillingError",
+                variant: "InvoiceNotFound",
+            }]
+        }
+    }
+
+    #[test]
+    fn test_error_catalog() {
+        register_error_catalog::<UserError>();
+        register_error_catalog::<UserError>();
+        let catalog = error_catalog();
+        assert_eq!(
+            catalog
+                .iter()
+                .filter(|d| d.code == "errors.test.catalog.not_found")
+                .count(),
+            1
+        );
+
+        let ts = error_catalog_typescript();
+        assert!(ts.contains("\n  | \"errors.test.catalog.not_found\""));
+        assert!(ts.contains("  \"errors.test.catalog.not_found\": [\"id\"],\n"));
+        assert!(error_catalog_json().contains("\"enum\": \"UserError\""));
+
+        register_error_catalog::<BillingError>();
+        let duplicates = duplicate_error_codes();
+        assert_eq!(
+            duplicates,
+            vec![(
+                "errors.test.catalog.not_found".to_string(),
+                vec![
+                    "BillingError::InvoiceNotFound".to_string(),
+                    "UserError::NotFound".to_string()
+                ]
+            )]
+        );
+        assert!(std::panic::catch_unwind(assert_unique_error_codes).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+use std::fmt;
+
+use super::MajordomeError;
+
+/// Convert library errors to specific `MajordomeError`s, the error is kept as the source.
+/// ```rs
+/// let id: i64 = id.parse().or_majordome("errors.users.invalid_id", 400)?;
+/// let user = db.get_user(id).await.context("loading user")?;
+/// ```
+pub trait ResultExt<T> {
+    /// Map the error to a code and status, the message is the error.
+    /// Server errors (>= 500) are internal errors: reported, with a generic message.
+    fn or_majordome(self, code: &str, status: u16) -> Result<T, MajordomeError>;
+
+    /// Convert to an internal error (like `?`), the context is added to the report.
+    fn context(self, context: &str) -> Result<T, MajordomeError>;
+}
+
+impl<T, E: std::error::Error + Send + Sync + 'static> ResultExt<T> for Result<T, E> {
+    fn or_majordome(self, code: &str, status: u16) -> Result<T, MajordomeError> {
+        self.map_err(|e| {
+            if status >= 500 {
+                let mut err = MajordomeError::from(e);
+                err.error = code.to_string();
+                err.status_code = status;
+                return err;
+            }
+
+            MajordomeError::new(code.to_string(), e.to_string(), vec![], status).with_source(e)
+        })
+    }
+
+    fn context(self, context: &str) -> Result<T, MajordomeError> {
+        self.map_err(|e| {
+            ContextError {
+                context: context.to_string(),
+                source: e,
+            }
+            .into()
+        })
+    }
+}
+
+/// Convert a missing value to a 404 `errors.service.not_found` error.
+/// ```rs
+/// let user = db.find_user(id).await.not_found_if_none()?;
+/// ```
+pub trait OptionExt<T> {
+    fn not_found_if_none(self) -> Result<T, MajordomeError>;
+}
+
+fn not_found() -> MajordomeError {
+    MajordomeError::new(
+        "errors.service.not_found".to_string(),
+        "Not found".to_string(),
+        vec![],
+        404,
+    )
+}
+
+impl<T> OptionExt<T> for Option<T> {
+    fn not_found_if_none(self) -> Result<T, MajordomeError> {
+        self.ok_or_else(not_found)
+    }
+}
+
+impl<T, E: Into<MajordomeError>> OptionExt<T> for Result<Option<T>, E> {
+    fn not_found_if_none(self) -> Result<T, MajordomeError> {
+        self.map_err(Into::into)?.ok_or_else(not_found)
+    }
+}
+
+/// An error with the context it happened in, see `ResultExt::context`.
+#[derive(Debug)]
+pub struct ContextError<E> {
+    pub context: String,
+    pub source: E,
+}
+
+impl<E> fmt::Display for ContextError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.context)
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for ContextError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<i64, std::num::ParseIntError> {
+        s.parse()
+    }
+
+    #[test]
+    fn test_result_ext() {
+        let err = parse("x")
+            .or_majordome("errors.test.invalid_id", 400)
+            .unwrap_err();
+        assert_eq!(err.error, "errors.test.invalid_id");
+        assert_eq!(err.message, "invalid digit found in string");
+        assert!(err.error_id.is_none());
+        assert!(err.downcast_source::<std::num::ParseIntError>().is_some());
+
+        let err = parse("x").or_majordome("errors.test.db", 503).unwrap_err();
+        assert_eq!(
+            (err.error.as_str(), err.status_code),
+            ("errors.test.db", 503)
+        );
+        assert!(err.error_id.is_some());
+
+        let err = parse("x").context("loading user").unwrap_err();
+        assert_eq!(err.status_code, 500);
+        let chain: Vec<String> = err.chain().map(|e| e.to_string()).collect();
+        assert_eq!(chain, vec!["loading user", "invalid digit found in string"]);
+
+        let found: Result<Option<i64>, MajordomeError> = Ok(None);
+        assert_eq!(found.not_found_if_none().unwrap_err().status_code, 404);
+        assert_eq!(Some(1).not_found_if_none().unwrap(), 1);
+        let failed: Result<Option<i64>, _> = parse("x").map(Some);
+        assert_eq!(failed.not_found_if_none().unwrap_err().status_code, 500);
+    }
+}
@@ -0,0 +1,251 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::RwLock;
+
+use super::MajordomeError;
+use crate::AppModBuilder;
+
+// locale -> error code -> message.
+static CATALOGS: RwLock<BTreeMap<String, HashMap<String, String>>> = RwLock::new(BTreeMap::new());
+
+/// Add translated messages for a locale, keyed by error code (eg. `errors.db.scylla.not_found`).
+/// Messages reference the error values as `{0}`, `{1}`... and the params by name, eg. `{id}`.
+pub fn add_error_catalog<I, K, V>(locale: &str, messages: I)
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: Into<String>,
+    V: Into<String>,
+{
+    let mut catalogs = CATALOGS.write().unwrap();
+    let catalog = catalogs.entry(locale.to_lowercase()).or_default();
+    for (code, message) in messages {
+        catalog.insert(code.into(), message.into());
+    }
+}
+
+/// Load a JSON catalog for a locale, returns the number of messages.
+/// Codes can be flat (`{"errors.db.not_found": "..."}`) or nested (`{"errors": {"db": {"not_found": "..."}}}`).
+pub fn load_error_catalog<P: AsRef<Path>>(locale: &str, path: P) -> std::io::Result<usize> {
+    let content = std::fs::read_to_string(path)?;
+    let value: serde_json::Value = serde_json::from_str(&content)?;
+
+    let mut messages = Vec::new();
+    flatten_catalog("", &value, &mut messages);
+    let count = messages.len();
+    add_error_catalog(locale, messages);
+    Ok(count)
+}
+
+/// Load every `<locale>.json` file of a directory, returns the loaded locales.
+pub fn load_error_catalogs<P: AsRef<Path>>(dir: P) -> std::io::Result<Vec<String>> {
+    let mut locales = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let Some(locale) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+
+        load_error_catalog(locale, &path)?;
+        locales.push(locale.to_lowercase());
+    }
+
+    locales.sort();
+    Ok(locales)
+}
+
+fn flatten_catalog(prefix: &str, value: &serde_json::Value, out: &mut Vec<(String, String)>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                let code = match prefix {
+                    "" => key.clone(),
+                    prefix => format!("{}.{}", prefix, key),
+                };
+                flatten_catalog(&code, value, out);
+            }
+        }
+        serde_json::Value::String(message) => out.push((prefix.to_string(), message.clone())),
+        _ => {}
+    }
+}
+
+/// Locales of an `Accept-Language` header, by preference.
+/// Regional locales are followed by their language, eg. `fr-CA` then `fr`.
+pub fn parse_accept_language(header: &str) -> Vec<String> {
+    let mut ranges: Vec<(f32, String)> = header
+        .split(',')
+        .filter_map(|range| {
+            let mut params = range.split(';').map(str::trim);
+            let locale = params.next()?.to_lowercase();
+            let q = params
+                .find_map(|p| p.strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.parse::<f32>().ok())?;
+
+            match locale.as_str() {
+                "" | "*" => None,
+                _ if q <= 0.0 => None,
+                _ => Some((q, locale)),
+            }
+        })
+        .collect();
+    // stable, equal weights keep the header order.
+    ranges.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let mut locales: Vec<String> = Vec::new();
+    for (_, locale) in ranges {
+        let language = locale.split('-').next().unwrap_or_default().to_string();
+        for l in [locale, language] {
+            if !locales.contains(&l) {
+                locales.push(l);
+            }
+        }
+    }
+    locales
+}
+
+/// Fill the placeholders in a single pass, so values containing `{...}` are kept as is.
+/// Unknown placeholders are left untouched.
+fn format_message(template: &str, err: &MajordomeError) -> String {
+    let mut message = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        message.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        let name = &rest[1..end];
+        match placeholder_value(name, err) {
+            Some(value) => message.push_str(&value),
+            None => message.push_str(&rest[..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+
+    message.push_str(rest);
+    message
+}
+
+fn placeholder_value(name: &str, err: &MajordomeError) -> Option<String> {
+    if let Ok(i) = name.parse::<usize>() {
+        return err.values.get(i).cloned();
+    }
+
+    match err.params.get(name)? {
+        serde_json::Value::String(s) => Some(s.clone()),
+        value => Some(value.to_string()),
+    }
+}
+
+impl MajordomeError {
+    /// Locale and message of the first locale having a translation for this error code.
+    pub fn localized_message<S: AsRef<str>>(&self, locales: &[S]) -> Option<(String, String)> {
+        let catalogs = CATALOGS.read().unwrap();
+        locales.iter().find_map(|locale| {
+            let locale = locale.as_ref().to_lowercase();
+            let template = catalogs.get(&locale)?.get(&self.error)?;
+            Some((locale, format_message(template, self)))
+        })
+    }
+
+    /// Translate the message for an `Accept-Language` header,
+    /// the message is kept when no catalog has a translation.
+    /// Error responses are only translated by the `negotiate_errors` middleware of the axum compat layer,
+    /// `IntoResponse` has no access to the request headers.
+    pub fn localize(mut self, accept_language: &str) -> Self {
+        if let Some((_, message)) = self.localized_message(&parse_accept_language(accept_language))
+        {
+            self.message = message;
+        }
+        self
+    }
+}
+
+impl AppModBuilder {
+    /// Load the error catalogs of a directory, see `load_error_catalogs`.
+    pub fn error_catalogs(self, dir: &str) -> Self {
+        match load_error_catalogs(dir) {
+            Ok(locales) => println!("🌐 Loaded error catalogs: {}", locales.join(", ")),
+            Err(e) => eprintln!("Failed to load error catalogs from {}: {}", dir, e),
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_localize() {
+        add_error_catalog(
+            "fr",
+            [("errors.test.i18n.not_found", "Utilisateur {0} introuvable")],
+        );
+        add_error_catalog(
+            "de-at",
+            [("errors.test.i18n.not_found", "Benutzer {id} fehlt")],
+        );
+
+        assert_eq!(
+            parse_accept_language("fr-CH, fr;q=0.9, en;q=0.8, *;q=0.5"),
+            vec!["fr-ch", "fr", "en"]
+        );
+
+        let err = MajordomeError::new(
+            "errors.test.i18n.not_found".to_string(),
+            "User 42 not found".to_string(),
+            vec!["42".to_string()],
+            404,
+        )
+        .with_param("id", &42);
+        assert_eq!(
+            err.clone().localize("fr-CH").message,
+            "Utilisateur 42 introuvable"
+        );
+        assert_eq!(
+            err.clone().localize("en, de-AT;q=0.5").message,
+            "Benutzer 42 fehlt"
+        );
+        assert_eq!(err.clone().localize("de").message, "User 42 not found");
+
+        let injected = MajordomeError::new(
+            "errors.test.i18n.not_found".to_string(),
+            "User {id} not found".to_string(),
+            vec!["{id}".to_string()],
+            404,
+        )
+        .with_param("id", &"{0}");
+        assert_eq!(
+            injected.clone().localize("fr").message,
+            "Utilisateur {id} introuvable"
+        );
+        assert_eq!(injected.localize("de-at").message, "Benutzer {0} fehlt");
+        assert_eq!(err.localize("fr;q=0").message, "User 42 not found");
+    }
+
+    #[test]
+    fn test_load_nested_catalog() {
+        let path = std::env::temp_dir().join(format!("majordome-i18n-{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"{"errors": {"test": {"i18n": {"conflict": "{0} existe déjà"}}}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(load_error_catalog("fr", &path).unwrap(), 1);
+        let err = MajordomeError::new(
+            "errors.test.i18n.conflict".to_string(),
+            "{0} already exists".to_string(),
+            vec!["bob".to_string()],
+            409,
+        );
+        assert_eq!(err.localize("fr").message, "bob existe déjà");
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -1,19 +1,47 @@
 use std::collections::BTreeMap;
+use std::sync::Arc;
 use uuid::Uuid;
 
+mod catalog;
+mod ext;
+mod i18n;
+mod params;
+mod reporter;
+mod response;
+pub use catalog::*;
+pub use ext::*;
+pub use i18n::*;
+pub use params::*;
+pub use reporter::*;
+
 #[cfg(feature = "actix")]
 use apistos_schemars as schemars;
 
-#[derive(Debug, serde::Serialize, Clone)]
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
 #[cfg_attr(feature = "actix", derive(apistos_schemars::JsonSchema))]
 #[cfg_attr(feature="axum", derive(schemars::JsonSchema))]
 #[non_exhaustive]
+// The rarely set fields are boxed to keep `Result<T, MajordomeError>` small.
 pub struct MajordomeError {
     pub error: String,
     pub message: String,
+    #[serde(default)]
     pub values: Vec<String>,
-    #[serde(skip_serializing)]
+    // Named values keeping their type, eg. `{"required": 3, "actual": 1}`, see `with_param`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: Box<BTreeMap<String, serde_json::Value>>,
+    // Not in the body, see `from_response`.
+    #[serde(skip_serializing, default)]
     pub status_code: u16,
+    // Response headers, eg. `Retry-After`, see `with_header`.
+    #[serde(skip)]
+    pub headers: Box<Vec<(String, String)>>,
+    // Id of the internal error, set when converted from another error.
+    #[serde(skip)]
+    pub error_id: Option<Box<Uuid>>,
+    // The error this one was converted from, see `source`.
+    #[serde(skip)]
+    pub source: Option<Arc<dyn std::error::Error + Send + Sync>>,
 }
 
 #[cfg(feature = "actix")]
@@ -43,28 +71,74 @@ impl MajordomeError {
             error,
             message,
             values,
+            params: Box::default(),
             status_code,
+            headers: Box::default(),
+            error_id: None,
+            source: None,
+        }
+    }
+
+    /// Add a response header, eg. `Retry-After` for a 429.
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// The status code if it is a valid HTTP status (100-599), 500 otherwise.
+    pub fn http_status(&self) -> u16 {
+        match self.status_code {
+            100..=599 => self.status_code,
+            _ => 500,
         }
     }
+
+    /// Keep the error causing this one.
+    pub fn with_source<E: std::error::Error + Send + Sync + 'static>(mut self, source: E) -> Self {
+        self.source = Some(Arc::new(source));
+        self
+    }
+
+    /// Same as `std::error::Error::source`.
+    /// `MajordomeError` can't implement `std::error::Error`, it would conflict with the `From<E: Error>` conversion.
+    pub fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.source {
+            Some(e) => Some(e.as_ref()),
+            None => None,
+        }
+    }
+
+    /// Iterate over the source chain, starting with the direct source.
+    pub fn chain(&self) -> impl Iterator<Item = &(dyn std::error::Error + 'static)> {
+        std::iter::successors(self.source(), |e| e.source())
+    }
+
+    /// Find an error of type T in the source chain.
+    /// ```rs
+    /// if let Some(e) = err.downcast_source::<std::io::Error>() {
+    ///     assert_eq!(e.kind(), std::io::ErrorKind::NotFound);
+    /// }
+    /// ```
+    pub fn downcast_source<T: std::error::Error + 'static>(&self) -> Option<&T> {
+        self.chain().find_map(|e| e.downcast_ref::<T>())
+    }
 }
 
-#[derive(Debug)]
-#[allow(dead_code)]
-struct InternalError {
-    pub id: Uuid,
-    pub inner: Box<dyn std::error::Error>,
+/// Report an unexpected error (converted to `MajordomeError`, or a captured panic), returns its id.
+/// See `ErrorReporter`.
+pub(crate) fn report_internal_error(
+    inner: &(dyn std::error::Error + 'static),
+    backtrace: Option<String>,
+) -> Uuid {
+    let error_id = Uuid::new_v4();
+    dispatch_error_report(ErrorReport::new(error_id, inner, backtrace));
+    error_id
 }
 
 impl<E: std::error::Error + Send + Sync + 'static> From<E> for MajordomeError {
     fn from(error: E) -> Self {
-        let error_id = Uuid::new_v4();
-        let error = InternalError {
-            id: error_id.clone(),
-            inner: Box::new(error),
-        };
-
-        // temporary: log the error
-        tracing::error!("{:?}", error);
+        let source: Arc<dyn std::error::Error + Send + Sync> = Arc::new(error);
+        let error_id = report_internal_error(source.as_ref(), None);
 
         MajordomeError {
             error: "errors.service.internal".to_string(),
@@ -73,7 +147,11 @@ impl<E: std::error::Error + Send + Sync + 'static> From<E> for MajordomeError {
                 error_id
             ),
             values: vec![error_id.to_string()],
+            params: Box::default(),
             status_code: 500,
+            headers: Box::default(),
+            error_id: Some(Box::new(error_id)),
+            source: Some(source),
         }
     }
 }
@@ -93,3 +171,44 @@ macro_rules! ensure {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct QueryError(std::io::Error);
+
+    impl std::fmt::Display for QueryError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "query failed")
+        }
+    }
+
+    impl std::error::Error for QueryError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn test_source_chain() {
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "missing table");
+        let err: MajordomeError = QueryError(io).into();
+
+        assert_eq!(err.status_code, 500);
+        assert_eq!(err.values, vec![err.error_id.as_ref().unwrap().to_string()]);
+        assert_eq!(err.chain().count(), 2);
+        assert!(err.downcast_source::<QueryError>().is_some());
+        assert_eq!(
+            err.downcast_source::<std::io::Error>().unwrap().kind(),
+            std::io::ErrorKind::NotFound
+        );
+
+        let json = serde_json::to_value(&err).unwrap();
+        assert!(json.get("source").is_none() && json.get("error_id").is_none());
+
+        let err = MajordomeError::new("errors.test".to_string(), "test".to_string(), vec![], 400);
+        assert!(err.source().is_none() && err.error_id.is_none());
+    }
+}
@@ -0,0 +1,164 @@
+use std::fmt::Display;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use super::MajordomeError;
+
+impl MajordomeError {
+    /// Add a named value to `params`, values that can't be serialized are set to null.
+    pub fn with_param<T: Serialize + ?Sized>(mut self, name: &str, value: &T) -> Self {
+        let value = serde_json::to_value(value).unwrap_or(Value::Null);
+        self.params.insert(name.to_string(), value);
+        self
+    }
+}
+
+// Used by the derive to convert the fields: serialized when they implement `Serialize`,
+// displayed as strings otherwise (autoref specialization, the Serialize impl has priority).
+#[doc(hidden)]
+pub struct __ParamValue<'a, T: ?Sized>(pub &'a T);
+
+#[doc(hidden)]
+pub trait __SerializeParam {
+    fn __param(&self) -> Value;
+}
+
+impl<T: Serialize + ?Sized> __SerializeParam for __ParamValue<'_, T> {
+    fn __param(&self) -> Value {
+        serde_json::to_value(self.0).unwrap_or(Value::Null)
+    }
+}
+
+#[doc(hidden)]
+pub trait __DisplayParam {
+    fn __param(&self) -> Value;
+}
+
+impl<T: Display + ?Sized> __DisplayParam for &__ParamValue<'_, T> {
+    fn __param(&self) -> Value {
+        Value::String(self.0.to_string())
+    }
+}
+
+// Used by the derived `TryFrom<MajordomeError>` to read the fields back: deserialized from `params`
+// (or from `values`) when they implement `Deserialize`, parsed from `values` with `FromStr` otherwise,
+// None when they implement neither: the variant is then never read back, see the derive docs.
+#[doc(hidden)]
+pub struct __ParamTarget<T>(PhantomData<T>);
+
+impl<T> __ParamTarget<T> {
+    pub const fn new() -> Self {
+        __ParamTarget(PhantomData)
+    }
+}
+
+impl<T> Default for __ParamTarget<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[doc(hidden)]
+pub trait __DeserializeParam<T> {
+    fn __from_param(&self, err: &MajordomeError, index: usize, name: &str) -> Option<T>;
+}
+
+impl<T: DeserializeOwned> __DeserializeParam<T> for &&__ParamTarget<T> {
+    fn __from_param(&self, err: &MajordomeError, index: usize, name: &str) -> Option<T> {
+        if let Some(param) = err.params.get(name) {
+            if let Ok(value) = serde_json::from_value(param.clone()) {
+                return Some(value);
+            }
+        }
+
+        // values are displayed: `3` for numbers, `bob` for strings.
+        let value = err.values.get(index)?;
+        serde_json::from_str(value)
+            .ok()
+            .or_else(|| serde_json::from_value(Value::String(value.clone())).ok())
+    }
+}
+
+#[doc(hidden)]
+pub trait __FromStrParam<T> {
+    fn __from_param(&self, err: &MajordomeError, index: usize, name: &str) -> Option<T>;
+}
+
+impl<T: FromStr> __FromStrParam<T> for &__ParamTarget<T> {
+    fn __from_param(&self, err: &MajordomeError, index: usize, _name: &str) -> Option<T> {
+        err.values.get(index)?.parse().ok()
+    }
+}
+
+#[doc(hidden)]
+pub trait __NoParam<T> {
+    fn __from_param(&self, err: &MajordomeError, index: usize, name: &str) -> Option<T>;
+}
+
+impl<T> __NoParam<T> for __ParamTarget<T> {
+    fn __from_param(&self, _err: &MajordomeError, _index: usize, _name: &str) -> Option<T> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Player(u32);
+
+    impl Display for Player {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "player #{}", self.0)
+        }
+    }
+
+    #[test]
+    fn test_params() {
+        let (required, player) = (3u32, Player(7));
+        let err = MajordomeError::new(
+            "errors.test.not_enough_players".to_string(),
+            "Not enough players".to_string(),
+            vec![required.to_string(), player.to_string()],
+            400,
+        );
+
+        let mut err = err.with_param("tags", &["a", "b"]);
+        err.params
+            .insert("required".to_string(), (&__ParamValue(&required)).__param());
+        err.params
+            .insert("player".to_string(), (&__ParamValue(&player)).__param());
+
+        assert_eq!(
+            serde_json::to_value(&err).unwrap()["params"],
+            serde_json::json!({"required": 3, "player": "player #7", "tags": ["a", "b"]})
+        );
+
+        let err = MajordomeError::new("errors.test".to_string(), "test".to_string(), vec![], 400);
+        assert!(serde_json::to_value(&err).unwrap().get("params").is_none());
+    }
+
+    #[test]
+    fn test_read_params() {
+        let err = MajordomeError::new(
+            "errors.test.not_enough_players".to_string(),
+            "Not enough players".to_string(),
+            vec!["3".to_string(), "bob".to_string(), "player #7".to_string()],
+            400,
+        )
+        .with_param("required", &4);
+
+        let required: Option<u32> =
+            (&&&__ParamTarget::<u32>::new()).__from_param(&err, 0, "required");
+        let name: Option<String> =
+            (&&&__ParamTarget::<String>::new()).__from_param(&err, 1, "name");
+        let player: Option<Player> =
+            (&&&__ParamTarget::<Player>::new()).__from_param(&err, 2, "player");
+        assert_eq!(required, Some(4));
+        assert_eq!(name.as_deref(), Some("bob"));
+        assert!(player.is_none());
+    }
+}
@@ -0,0 +1,620 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    future::Future,
+    io::Write,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, RwLock,
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::MajordomeApp;
+
+/// An internal error, as received by the reporters:
+/// errors converted to `MajordomeError` and panics captured by the panic hook.
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorReport {
+    pub error_id: Uuid,
+    pub message: String,
+    // Display of the sources, starting with the direct source.
+    pub chain: Vec<String>,
+    pub debug: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<ErrorContext>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backtrace: Option<String>,
+    // Unix timestamp, in milliseconds.
+    pub timestamp: u64,
+    // Identical errors suppressed since the last report, see `Deduplicated`.
+    pub suppressed: u64,
+}
+
+impl ErrorReport {
+    pub(crate) fn new(
+        error_id: Uuid,
+        error: &(dyn std::error::Error + 'static),
+        backtrace: Option<String>,
+    ) -> Self {
+        let chain = std::iter::successors(error.source(), |e| e.source())
+            .map(|e| e.to_string())
+            .collect();
+
+        let backtrace = backtrace.or_else(|| {
+            let bt = std::backtrace::Backtrace::capture();
+            match bt.status() {
+                std::backtrace::BacktraceStatus::Captured => Some(bt.to_string()),
+                _ => None,
+            }
+        });
+
+        ErrorReport {
+            error_id,
+            message: error.to_string(),
+            chain,
+            debug: format!("{:?}", error),
+            context: ErrorContext::current(),
+            backtrace,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            suppressed: 0,
+        }
+    }
+
+    /// Identifies identical errors, regardless of their id.
+    pub fn fingerprint(&self) -> String {
+        let mut fingerprint = self.message.clone();
+        for source in &self.chain {
+            fingerprint.push('\n');
+            fingerprint.push_str(source);
+        }
+        fingerprint
+    }
+}
+
+/// Context of the request being handled when the error happened.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ErrorContext {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, String>,
+}
+
+tokio::task_local! {
+    static ERROR_CONTEXT: ErrorContext;
+}
+
+impl ErrorContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn request_id(mut self, request_id: &str) -> Self {
+        self.request_id = Some(request_id.to_string());
+        self
+    }
+
+    pub fn method(mut self, method: &str) -> Self {
+        self.method = Some(method.to_string());
+        self
+    }
+
+    pub fn path(mut self, path: &str) -> Self {
+        self.path = Some(path.to_string());
+        self
+    }
+
+    pub fn extra(mut self, key: &str, value: &str) -> Self {
+        self.extra.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Attach this context to the errors reported while running f, eg. in a middleware.
+    /// ```rs
+    /// let ctx = ErrorContext::new().method(req.method().as_str()).path(req.uri().path());
+    /// ctx.scope(next.run(req)).await
+    /// ```
+    pub async fn scope<F: Future>(self, f: F) -> F::Output {
+        ERROR_CONTEXT.scope(self, f).await
+    }
+
+    /// Context of the current task, if any.
+    pub fn current() -> Option<Self> {
+        ERROR_CONTEXT.try_with(|c| c.clone()).ok()
+    }
+}
+
+/// Receives the internal errors.
+/// Reporters are called synchronously where the error happens, they must not block.
+pub trait ErrorReporter: Send + Sync {
+    fn report(&self, report: &ErrorReport);
+
+    /// Forward the reports held back by the reporter, all of them if force.
+    /// Called every second while an app runs, and with force when it stops.
+    fn flush(&self, _force: bool) {}
+}
+
+type Reporters = RwLock<Vec<(u64, Arc<dyn ErrorReporter>)>>;
+
+fn reporters() -> &'static Reporters {
+    static REPORTERS: Reporters = RwLock::new(Vec::new());
+    &REPORTERS
+}
+
+/// Register a reporter for the whole process: it receives the errors of every app.
+/// When no reporter is registered, errors are logged with `TracingReporter`.
+/// Returns an id for `unregister_global_error_reporter`.
+pub fn register_global_error_reporter<R: ErrorReporter + 'static>(reporter: R) -> u64 {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    reporters().write().unwrap().push((id, Arc::new(reporter)));
+    id
+}
+
+pub fn unregister_global_error_reporter(id: u64) {
+    reporters().write().unwrap().retain(|(i, _)| *i != id);
+}
+
+/// Send an internal error to the reporters.
+pub(crate) fn dispatch_error_report(report: ErrorReport) {
+    let reporters: Vec<_> = reporters()
+        .read()
+        .unwrap()
+        .iter()
+        .map(|(_, r)| r.clone())
+        .collect();
+    if reporters.is_empty() {
+        TracingReporter.report(&report);
+    }
+
+    for reporter in reporters {
+        reporter.report(&report);
+    }
+}
+
+/// Flush the process-wide reporters, see `ErrorReporter::flush`.
+pub(crate) fn flush_error_reporters(force: bool) {
+    let reporters: Vec<_> = reporters()
+        .read()
+        .unwrap()
+        .iter()
+        .map(|(_, r)| r.clone())
+        .collect();
+
+    for reporter in reporters {
+        reporter.flush(force);
+    }
+}
+
+impl MajordomeApp {
+    /// Flush the expired deduplication windows of the reporters while the app runs.
+    pub(crate) fn _start_error_flush_probe(&self) {
+        let s = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                s.sleep_until_closing(Duration::from_secs(1), true).await;
+                if s.is_closing() {
+                    break;
+                }
+                flush_error_reporters(false);
+            }
+        });
+    }
+}
+
+/// Log the errors with `tracing::error!`.
+pub struct TracingReporter;
+
+impl ErrorReporter for TracingReporter {
+    fn report(&self, report: &ErrorReport) {
+        tracing::error!(error_id = %report.error_id, "{}", report.debug);
+    }
+}
+
+/// Append the errors to a file, one JSON object per line.
+pub struct JsonLinesReporter {
+    file: Mutex<std::fs::File>,
+}
+
+impl JsonLinesReporter {
+    pub fn new(path: &str) -> std::io::Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+
+        Ok(JsonLinesReporter {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl ErrorReporter for JsonLinesReporter {
+    fn report(&self, report: &ErrorReport) {
+        let Ok(mut line) = serde_json::to_vec(report) else {
+            return;
+        };
+        line.push(b'\n');
+
+        if let Err(e) = self.file.lock().unwrap().write_all(&line) {
+            eprintln!("Failed to write error report {}: {}", report.error_id, e);
+        }
+    }
+}
+
+/// POST the errors as JSON to a webhook, from a background task.
+#[cfg(feature = "http")]
+pub struct WebhookReporter {
+    url: String,
+    headers: Vec<(String, String)>,
+    timeout: Duration,
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "http")]
+impl WebhookReporter {
+    pub fn new(url: &str) -> Self {
+        WebhookReporter {
+            url: url.to_string(),
+            headers: Vec::new(),
+            timeout: Duration::from_secs(10),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+#[cfg(feature = "http")]
+impl ErrorReporter for WebhookReporter {
+    fn report(&self, report: &ErrorReport) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            eprintln!(
+                "Failed to send error report {}: no tokio runtime.",
+                report.error_id
+            );
+            return;
+        };
+
+        let mut req = self.client.post(&self.url).timeout(self.timeout);
+        for (k, v) in &self.headers {
+            req = req.header(k, v);
+        }
+        let Ok(body) = serde_json::to_vec(report) else {
+            return;
+        };
+        let req = req.header("content-type", "application/json").body(body);
+        let error_id = report.error_id;
+
+        runtime.spawn(async move {
+            match req.send().await {
+                Ok(resp) if resp.status().is_success() => {}
+                Ok(resp) => eprintln!(
+                    "Failed to send error report {}: HTTP {}",
+                    error_id,
+                    resp.status()
+                ),
+                Err(e) => eprintln!("Failed to send error report {}: {}", error_id, e),
+            }
+        });
+    }
+}
+
+/// Only forward a part of the errors, rate is between 0 and 1.
+/// The choice depends on the error id, so all the reporters sampled with the same rate agree.
+pub struct Sampled<R> {
+    inner: R,
+    rate: f64,
+}
+
+impl<R: ErrorReporter> Sampled<R> {
+    pub fn new(inner: R, rate: f64) -> Self {
+        Sampled {
+            inner,
+            rate: rate.clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl<R: ErrorReporter> ErrorReporter for Sampled<R> {
+    fn report(&self, report: &ErrorReport) {
+        let bucket = (report.error_id.as_u128() % 10_000) as f64 / 10_000.0;
+        if bucket < self.rate {
+            self.inner.report(report);
+        }
+    }
+
+    fn flush(&self, force: bool) {
+        self.inner.flush(force);
+    }
+}
+
+/// Forward an error once per window, identical errors (see `ErrorReport::fingerprint`)
+/// are counted in `suppressed`: the last of them is forwarded when the window expires,
+/// or when the app stops.
+pub struct Deduplicated<R: ErrorReporter> {
+    inner: R,
+    window: Duration,
+    seen: Mutex<HashMap<String, Window>>,
+}
+
+struct Window {
+    start: Instant,
+    // Last suppressed report, with the suppressed count.
+    suppressed: Option<ErrorReport>,
+}
+
+impl<R: ErrorReporter> Deduplicated<R> {
+    pub fn new(inner: R, window: Duration) -> Self {
+        Deduplicated {
+            inner,
+            window,
+            seen: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<R: ErrorReporter> ErrorReporter for Deduplicated<R> {
+    fn report(&self, report: &ErrorReport) {
+        self.flush(false);
+
+        {
+            let mut seen = self.seen.lock().unwrap();
+            if let Some(w) = seen.get_mut(&report.fingerprint()) {
+                let suppressed = w.suppressed.as_ref().map_or(0, |r| r.suppressed);
+                let mut report = report.clone();
+                report.suppressed += suppressed + 1;
+                w.suppressed = Some(report);
+                return;
+            }
+
+            seen.insert(
+                report.fingerprint(),
+                Window {
+                    start: Instant::now(),
+                    suppressed: None,
+                },
+            );
+        }
+
+        self.inner.report(report);
+    }
+
+    /// Forward the suppressed errors of the expired windows, all of them if force.
+    /// Also called on every report and when dropped.
+    fn flush(&self, force: bool) {
+        let now = Instant::now();
+        let expired: Vec<ErrorReport> = {
+            let mut seen = self.seen.lock().unwrap();
+            let mut expired = Vec::new();
+            seen.retain(|_, w| {
+                if !force && now.duration_since(w.start) < self.window {
+                    return true;
+                }
+                expired.extend(w.suppressed.take());
+                false
+            });
+            expired
+        };
+
+        for report in expired {
+            self.inner.report(&report);
+        }
+        self.inner.flush(force);
+    }
+}
+
+impl<R: ErrorReporter> Drop for Deduplicated<R> {
+    fn drop(&mut self) {
+        self.flush(true);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MajordomeError;
+
+    #[derive(Clone, Default)]
+    struct Collect(Arc<Mutex<Vec<ErrorReport>>>);
+
+    impl ErrorReporter for Collect {
+        fn report(&self, report: &ErrorReport) {
+            self.0.lock().unwrap().push(report.clone());
+        }
+    }
+
+    fn report(message: &str) -> ErrorReport {
+        let e = std::io::Error::new(std::io::ErrorKind::Other, message.to_string());
+        ErrorReport::new(Uuid::new_v4(), &e, None)
+    }
+
+    #[tokio::test]
+    async fn test_reporter_receives_internal_errors() {
+        let collect = Collect::default();
+        let id = register_global_error_reporter(collect.clone());
+
+        let ctx = ErrorContext::new().method("GET").path("/users");
+        let err: MajordomeError = ctx
+            .scope(async { std::io::Error::new(std::io::ErrorKind::Other, "disk full").into() })
+            .await;
+
+        let reports = collect.0.lock().unwrap();
+        let report = reports
+            .iter()
+            .find(|r| err.error_id.as_deref() == Some(&r.error_id))
+            .unwrap();
+        assert_eq!(report.message, "disk full");
+        assert_eq!(
+            report.context.as_ref().unwrap().path.as_deref(),
+            Some("/users")
+        );
+        drop(reports);
+
+        unregister_global_error_reporter(id);
+        let _: MajordomeError = std::io::Error::new(std::io::ErrorKind::Other, "late").into();
+        assert!(!collect
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|r| r.message == "late"));
+    }
+
+    #[test]
+    fn test_sampled_and_deduplicated() {
+        let none = Collect::default();
+        let all = Collect::default();
+        let (s0, s1) = (
+            Sampled::new(none.clone(), 0.0),
+            Sampled::new(all.clone(), 1.0),
+        );
+        for _ in 0..10 {
+            let r = report("x");
+            s0.report(&r);
+            s1.report(&r);
+        }
+        assert_eq!(none.0.lock().unwrap().len(), 0);
+        assert_eq!(all.0.lock().unwrap().len(), 10);
+
+        let collect = Collect::default();
+        let dedup = Deduplicated::new(collect.clone(), Duration::from_secs(3600));
+        for msg in ["a", "a", "b", "a"] {
+            dedup.report(&report(msg));
+        }
+        let messages = |c: &Collect| -> Vec<(String, u64)> {
+            let reports = c.0.lock().unwrap();
+            reports
+                .iter()
+                .map(|r| (r.message.clone(), r.suppressed))
+                .collect()
+        };
+        assert_eq!(
+            messages(&collect),
+            vec![("a".to_string(), 0), ("b".to_string(), 0)]
+        );
+        drop(dedup);
+        assert_eq!(messages(&collect)[2], ("a".to_string(), 2));
+
+        // expired windows are flushed and evicted on the next report.
+        let collect = Collect::default();
+        let dedup = Deduplicated::new(collect.clone(), Duration::from_millis(10));
+        dedup.report(&report("c"));
+        dedup.report(&report("c"));
+        std::thread::sleep(Duration::from_millis(20));
+        dedup.report(&report("d"));
+        assert_eq!(
+            messages(&collect),
+            vec![
+                ("c".to_string(), 0),
+                ("c".to_string(), 1),
+                ("d".to_string(), 0)
+            ]
+        );
+        assert_eq!(dedup.seen.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_deduplicated_flushed_on_stop() {
+        let collect = Collect::default();
+        let id = register_global_error_reporter(Deduplicated::new(
+            collect.clone(),
+            Duration::from_secs(3600),
+        ));
+        let app = MajordomeApp::builder().await.build().await;
+
+        for _ in 0..3 {
+            let _: MajordomeError = std::io::Error::new(std::io::ErrorKind::Other, "burst").into();
+        }
+        let bursts = || -> Vec<u64> {
+            let reports = collect.0.lock().unwrap();
+            reports
+                .iter()
+                .filter(|r| r.message == "burst")
+                .map(|r| r.suppressed)
+                .collect()
+        };
+        assert_eq!(bursts(), vec![0]);
+
+        // no further report, the count is emitted when the app stops.
+        app.stop().await;
+        assert_eq!(bursts(), vec![0, 2]);
+        unregister_global_error_reporter(id);
+    }
+
+    #[test]
+    fn test_json_lines_reporter() {
+        let path = std::env::temp_dir().join(format!("majordome-errors-{}.jsonl", Uuid::new_v4()));
+        let reporter = JsonLinesReporter::new(path.to_str().unwrap()).unwrap();
+        reporter.report(&report("first"));
+        reporter.report(&report("second"));
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = content
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1]["message"], "second");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[cfg(feature = "http")]
+    #[tokio::test]
+    async fn test_webhook_reporter() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = tokio::spawn(async move {
+            let (mut sock, _) = listener.accept().await.unwrap();
+            let mut req = Vec::new();
+            let mut buf = vec![0; 4096];
+            // read until the JSON body is complete.
+            while !req.ends_with(b"}") {
+                let n = sock.read(&mut buf).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                req.extend_from_slice(&buf[..n]);
+            }
+            sock.write_all(b"HTTP/1.1 204 No Content\r\nconnection: close\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8_lossy(&req).to_string()
+        });
+
+        WebhookReporter::new(&format!("http://{}/errors", addr))
+            .header("X-Token", "t0ken")
+            .report(&report("webhook"));
+
+        let req = tokio::time::timeout(Duration::from_secs(5), server)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(req.starts_with("POST /errors"));
+        assert!(req.to_lowercase().contains("x-token: t0ken"));
+        assert!(req.contains("\"message\":\"webhook\""));
+    }
+}
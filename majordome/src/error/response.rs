@@ -0,0 +1,101 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use super::MajordomeError;
+
+// RFC 7807 body, see `ProblemDetails` in the axum compat layer.
+#[derive(Deserialize)]
+struct ProblemBody {
+    #[serde(rename = "type")]
+    type_: String,
+    code: Option<String>,
+    title: Option<String>,
+    detail: Option<String>,
+    #[serde(default)]
+    values: Vec<String>,
+    #[serde(default)]
+    params: BTreeMap<String, serde_json::Value>,
+    error_id: Option<uuid::Uuid>,
+}
+
+impl MajordomeError {
+    /// Read the error returned by another service, from its status and body.
+    /// Both the majordome and the problem+json bodies are supported,
+    /// other bodies become an `errors.http.upstream` error with the body as message.
+    pub fn from_response_body(status_code: u16, body: &[u8]) -> Self {
+        if let Ok(mut err) = serde_json::from_slice::<MajordomeError>(body) {
+            err.status_code = status_code;
+            return err;
+        }
+
+        if let Ok(problem) = serde_json::from_slice::<ProblemBody>(body) {
+            let mut err = MajordomeError::new(
+                problem.code.unwrap_or(problem.type_),
+                problem.detail.or(problem.title).unwrap_or_default(),
+                problem.values,
+                status_code,
+            );
+            *err.params = problem.params;
+            err.error_id = problem.error_id.map(Box::new);
+            return err;
+        }
+
+        MajordomeError::new(
+            "errors.http.upstream".to_string(),
+            String::from_utf8_lossy(body).to_string(),
+            vec![status_code.to_string()],
+            status_code,
+        )
+    }
+
+    /// Read the error of an `http::Response`, see `from_response_body`.
+    pub fn from_response<B: AsRef<[u8]>>(response: &http::Response<B>) -> Self {
+        Self::from_response_body(response.status().as_u16(), response.body().as_ref())
+    }
+
+    /// Read the error of a `reqwest::Response`, see `from_response_body`.
+    /// ```rs
+    /// let resp = client.get(url).send().await?;
+    /// if !resp.status().is_success() {
+    ///     return Err(MajordomeError::from_reqwest(resp).await);
+    /// }
+    /// ```
+    #[cfg(feature = "http")]
+    pub async fn from_reqwest(response: reqwest::Response) -> Self {
+        let status_code = response.status().as_u16();
+        match response.bytes().await {
+            Ok(body) => Self::from_response_body(status_code, &body),
+            Err(e) => MajordomeError::from(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_response() {
+        let resp = http::Response::builder()
+            .status(404)
+            .body(r#"{"error":"errors.users.not_found","message":"User 42 not found","values":["42"],"params":{"id":42}}"#)
+            .unwrap();
+        let err = MajordomeError::from_response(&resp);
+        assert_eq!(err.error, "errors.users.not_found");
+        assert_eq!((err.status_code, err.values.len()), (404, 1));
+        assert_eq!(err.params["id"], 42);
+
+        let body = r#"{"type":"https://docs.example.com/errors/x","title":"Conflict","status":409,"detail":"bob exists","code":"errors.users.conflict","values":["bob"]}"#;
+        let err = MajordomeError::from_response_body(409, body.as_bytes());
+        assert_eq!(err.error, "errors.users.conflict");
+        assert_eq!(err.message, "bob exists");
+
+        let err = MajordomeError::from_response_body(502, b"Bad Gateway");
+        assert_eq!(err.error, "errors.http.upstream");
+        assert_eq!(
+            (err.message.as_str(), err.status_code),
+            ("Bad Gateway", 502)
+        );
+    }
+}
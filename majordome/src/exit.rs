@@ -0,0 +1,128 @@
+use std::{fmt, str::FromStr};
+
+use crate::ConfigValueError;
+
+/// Exit code used when a second signal forces the process to exit
+/// before the modules are stopped.
+pub const FORCED_EXIT_CODE: i32 = 99;
+
+/// Signals starting the exit phase of the app, see `MAJORDOME_EXIT_SIGNALS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExitSignal {
+    Interrupt,
+    Terminate,
+    Quit,
+}
+
+impl fmt::Display for ExitSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExitSignal::Interrupt => write!(f, "SIGINT"),
+            ExitSignal::Terminate => write!(f, "SIGTERM"),
+            ExitSignal::Quit => write!(f, "SIGQUIT"),
+        }
+    }
+}
+
+impl FromStr for ExitSignal {
+    type Err = ConfigValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let upper = s.trim().to_uppercase();
+        match upper.strip_prefix("SIG").unwrap_or(&upper) {
+            "INT" => Ok(ExitSignal::Interrupt),
+            "TERM" => Ok(ExitSignal::Terminate),
+            "QUIT" => Ok(ExitSignal::Quit),
+            _ => Err(ConfigValueError {
+                value: s.to_string(),
+                expected: "exit signal (SIGINT, SIGTERM, SIGQUIT)",
+            }),
+        }
+    }
+}
+
+/// Why the app started exiting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ExitReason {
+    /// A signal was received.
+    Signal(ExitSignal),
+    /// A module called `MajordomeApp::request_exit`.
+    Requested { module: String, message: String },
+    /// A critical task ended while the app was running.
+    TaskFailed {
+        module: String,
+        task: String,
+        message: String,
+    },
+    /// The main future given to `MajordomeApp::run` returned an error or panicked.
+    MainFailed { message: String },
+    /// The app was stopped without an exit request, the main function completed.
+    Completed,
+}
+
+impl ExitReason {
+    pub fn requested(module: &str, message: &str) -> Self {
+        ExitReason::Requested {
+            module: module.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Whether the app exited because something went wrong.
+    pub fn is_failure(&self) -> bool {
+        matches!(
+            self,
+            ExitReason::Requested { .. }
+                | ExitReason::TaskFailed { .. }
+                | ExitReason::MainFailed { .. }
+        )
+    }
+
+    /// Process exit code for this reason: 0 for a graceful exit, 1 for a failure.
+    pub fn exit_code(&self) -> i32 {
+        match self.is_failure() {
+            true => 1,
+            false => 0,
+        }
+    }
+}
+
+impl fmt::Display for ExitReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExitReason::Signal(s) => write!(f, "received {}", s),
+            ExitReason::Requested { module, message } => {
+                write!(f, "requested by {}: {}", module, message)
+            }
+            ExitReason::TaskFailed {
+                module,
+                task,
+                message,
+            } => write!(f, "task {} ({}) failed: {}", task, module, message),
+            ExitReason::MainFailed { message } => write!(f, "main failed: {}", message),
+            ExitReason::Completed => write!(f, "completed"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CommaList;
+
+    #[test]
+    fn test_parse_exit_signals() {
+        let signals: CommaList<ExitSignal> = "SIGINT, term,sigquit".parse().unwrap();
+        assert_eq!(
+            signals.0,
+            vec![
+                ExitSignal::Interrupt,
+                ExitSignal::Terminate,
+                ExitSignal::Quit
+            ]
+        );
+        assert_eq!(signals.to_string(), "SIGINT,SIGTERM,SIGQUIT");
+        assert!("SIGKILL".parse::<ExitSignal>().is_err());
+    }
+}
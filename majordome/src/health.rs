@@ -0,0 +1,205 @@
+use std::time::{Duration, Instant};
+
+use serde::{Serialize, Serializer};
+use tokio::task::JoinSet;
+
+use crate::{LifecycleState, MajordomeApp};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthState {
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
+
+/// Result of a module health check, see `AppModRuntime::health`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HealthStatus {
+    pub state: HealthState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    // Measured by the app when not set by the module.
+    #[serde(rename = "latency_ms", serialize_with = "serialize_ms")]
+    pub latency: Option<Duration>,
+}
+
+impl HealthStatus {
+    pub fn healthy() -> Self {
+        HealthStatus {
+            state: HealthState::Healthy,
+            details: None,
+            latency: None,
+        }
+    }
+
+    pub fn degraded(details: &str) -> Self {
+        HealthStatus {
+            state: HealthState::Degraded,
+            details: Some(details.to_string()),
+            latency: None,
+        }
+    }
+
+    pub fn unhealthy(details: &str) -> Self {
+        HealthStatus {
+            state: HealthState::Unhealthy,
+            details: Some(details.to_string()),
+            latency: None,
+        }
+    }
+
+    pub fn details(mut self, details: &str) -> Self {
+        self.details = Some(details.to_string());
+        self
+    }
+
+    pub fn latency(mut self, latency: Duration) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+}
+
+fn serialize_ms<S: Serializer>(d: &Option<Duration>, s: S) -> Result<S::Ok, S::Error> {
+    match d {
+        Some(d) => s.serialize_f64(d.as_secs_f64() * 1000.0),
+        None => s.serialize_none(),
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleHealth {
+    pub module: String,
+    #[serde(flatten)]
+    pub status: HealthStatus,
+}
+
+/// Health of the app, the state is the worst state of the modules.
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub state: HealthState,
+    pub lifecycle: LifecycleState,
+    pub ready: bool,
+    pub modules: Vec<ModuleHealth>,
+}
+
+impl MajordomeApp {
+    /// Run the health checks of all the modules concurrently, see `MAJORDOME_HEALTH_CHECK_TIMEOUT`.
+    pub async fn health_report(&self) -> HealthReport {
+        self.health_report_with_timeout(self.settings.health_check_timeout)
+            .await
+    }
+
+    /// Same as health_report, checks not completed after timeout are unhealthy.
+    pub async fn health_report_with_timeout(&self, timeout: Duration) -> HealthReport {
+        let modules = self.modules.modules_refs.lock().await.clone();
+
+        let mut set = JoinSet::new();
+        for (i, (_, module)) in modules.iter().enumerate() {
+            let (app, module) = (self.clone(), module.clone());
+            set.spawn(async move {
+                let start = Instant::now();
+                let status = match tokio::time::timeout(timeout, module.health(app)).await {
+                    Ok(status) => status,
+                    Err(_) => HealthStatus::unhealthy(&format!(
+                        "health check timed out after {:?}",
+                        timeout
+                    )),
+                };
+
+                match status.latency {
+                    Some(_) => (i, status),
+                    None => (i, status.latency(start.elapsed())),
+                }
+            });
+        }
+
+        let mut statuses: Vec<Option<HealthStatus>> = vec![None; modules.len()];
+        while let Some(res) = set.join_next().await {
+            if let Ok((i, status)) = res {
+                statuses[i] = Some(status);
+            }
+        }
+
+        let modules: Vec<ModuleHealth> = modules
+            .into_iter()
+            .zip(statuses)
+            .map(|((module, _), status)| ModuleHealth {
+                module,
+                status: status.unwrap_or_else(|| HealthStatus::unhealthy("health check panicked")),
+            })
+            .collect();
+
+        HealthReport {
+            state: modules
+                .iter()
+                .map(|m| m.status.state)
+                .max()
+                .unwrap_or(HealthState::Healthy),
+            lifecycle: self.lifecycle_state(),
+            ready: self.is_ready(),
+            modules,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::AppModRuntime;
+
+    struct Check(HealthStatus, Duration);
+
+    #[async_trait]
+    impl AppModRuntime for Check {
+        async fn health(&self, _app: MajordomeApp) -> HealthStatus {
+            tokio::time::sleep(self.1).await;
+            self.0.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_health_report() {
+        let app = MajordomeApp::new().await;
+        let report = app.health_report().await;
+        assert_eq!(report.state, HealthState::Healthy);
+        assert!(report.modules.is_empty());
+
+        let checks = [
+            ("ok", HealthStatus::healthy(), 0),
+            ("slow", HealthStatus::degraded("lagging"), 0),
+            ("stuck", HealthStatus::healthy(), 1000),
+        ];
+        for (name, status, ms) in checks {
+            let module = Arc::new(Check(status, Duration::from_millis(ms)));
+            app.modules
+                .modules_refs
+                .lock()
+                .await
+                .push((name.to_string(), module));
+        }
+
+        let report = app
+            .health_report_with_timeout(Duration::from_millis(50))
+            .await;
+        let states: Vec<_> = report.modules.iter().map(|m| m.status.state).collect();
+        assert_eq!(
+            states,
+            vec![
+                HealthState::Healthy,
+                HealthState::Degraded,
+                HealthState::Unhealthy
+            ]
+        );
+        assert_eq!(report.state, HealthState::Unhealthy);
+        assert!(report.modules[0].status.latency.is_some());
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["modules"][1]["details"], "lagging");
+        assert_eq!(json["lifecycle"], "ready");
+    }
+}
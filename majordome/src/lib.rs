@@ -1,13 +1,29 @@
 mod app;
 mod compat;
 mod error;
+mod exit;
+mod health;
+mod lifecycle;
 mod module;
+mod panic;
+mod phase;
+mod run;
+mod settings;
+mod shutdown;
 mod signal;
+mod systemd;
 
 pub use app::*;
 pub use compat::*;
 pub use error::*;
+pub use exit::*;
+pub use health::*;
+pub use lifecycle::*;
 pub use module::*;
+pub use panic::TaskPanic;
+pub use phase::*;
+pub use shutdown::*;
+pub use systemd::SdNotifier;
 
 pub mod macros {
     pub use majordome_derive::*;
@@ -0,0 +1,180 @@
+use std::{
+    fmt,
+    sync::{atomic::Ordering, Arc},
+};
+
+use serde::Serialize;
+use tokio::sync::watch;
+
+use crate::{signal::MajordomeSignal, AppModBuilder, MajordomeApp};
+
+/// State of the app, transitions only go forward:
+/// - Starting: modules are loading, or a readiness gate is held.
+/// - Ready: the app was built and all readiness gates were released.
+/// - Draining: the app is exiting, the shutdown phases are running.
+/// - Closing: the modules are being stopped (`ShutdownPhase::Stop`).
+/// - Stopped: `stop` completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LifecycleState {
+    Starting,
+    Ready,
+    Draining,
+    Closing,
+    Stopped,
+}
+
+impl fmt::Display for LifecycleState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LifecycleState::Starting => write!(f, "starting"),
+            LifecycleState::Ready => write!(f, "ready"),
+            LifecycleState::Draining => write!(f, "draining"),
+            LifecycleState::Closing => write!(f, "closing"),
+            LifecycleState::Stopped => write!(f, "stopped"),
+        }
+    }
+}
+
+/// Holds the readiness of the app back until released or dropped,
+/// eg. while a cache is warming up.
+/// A gate taken once the app is ready makes `is_ready` false without changing the state.
+#[must_use = "the gate is released when dropped"]
+pub struct ReadinessGate {
+    signal: Arc<MajordomeSignal>,
+    id: u64,
+}
+
+impl ReadinessGate {
+    fn new(signal: Arc<MajordomeSignal>, name: &str) -> Self {
+        let id = signal.next_gate_id.fetch_add(1, Ordering::Relaxed);
+        signal
+            .readiness_gates
+            .lock()
+            .unwrap()
+            .insert(id, name.to_string());
+
+        ReadinessGate { signal, id }
+    }
+
+    pub fn release(self) {}
+}
+
+impl Drop for ReadinessGate {
+    fn drop(&mut self) {
+        self.signal.readiness_gates.lock().unwrap().remove(&self.id);
+        self.signal.try_ready();
+    }
+}
+
+impl MajordomeSignal {
+    /// Move to a later state, earlier states are ignored.
+    pub(crate) fn set_lifecycle(&self, state: LifecycleState) -> bool {
+        let changed = self.lifecycle.send_if_modified(|s| {
+            if *s < state {
+                *s = state;
+                return true;
+            }
+            false
+        });
+
+        if changed {
+            self.sd_notify_lifecycle(state);
+        }
+        changed
+    }
+
+    /// The app was built, it becomes ready once the gates are released.
+    pub(crate) fn mark_started(&self) {
+        self.started.store(true, Ordering::SeqCst);
+        self.try_ready();
+    }
+
+    fn try_ready(&self) {
+        let gates = self.readiness_gates.lock().unwrap();
+        if !self.started.load(Ordering::SeqCst) || !gates.is_empty() {
+            return;
+        }
+
+        if self.set_lifecycle(LifecycleState::Ready) {
+            println!("✅ App is ready.");
+        }
+    }
+}
+
+impl MajordomeApp {
+    pub fn lifecycle_state(&self) -> LifecycleState {
+        *self.signal.lifecycle.borrow()
+    }
+
+    /// Whether the app should receive traffic: the state is Ready and no readiness gate is held.
+    pub fn is_ready(&self) -> bool {
+        self.lifecycle_state() == LifecycleState::Ready
+            && self.signal.readiness_gates.lock().unwrap().is_empty()
+    }
+
+    /// Observe the lifecycle state changes.
+    pub fn watch_lifecycle(&self) -> watch::Receiver<LifecycleState> {
+        self.signal.lifecycle.subscribe()
+    }
+
+    /// Wait until the app reaches a state (or a later one).
+    pub async fn wait_for_lifecycle_state(&self, state: LifecycleState) {
+        let mut rx = self.watch_lifecycle();
+        let _ = rx.wait_for(|s| *s >= state).await;
+    }
+
+    /// Hold the readiness of the app back until the gate is released.
+    pub fn readiness_gate(&self, name: &str) -> ReadinessGate {
+        ReadinessGate::new(self.signal.clone(), name)
+    }
+
+    /// Names of the readiness gates currently held.
+    pub fn pending_readiness_gates(&self) -> Vec<String> {
+        let gates = self.signal.readiness_gates.lock().unwrap();
+        gates.values().cloned().collect()
+    }
+}
+
+impl AppModBuilder {
+    /// Hold the readiness of the app back until the gate is released, eg. in `AppMod::init`.
+    pub fn readiness_gate(&self, name: &str) -> ReadinessGate {
+        ReadinessGate::new(self.app.signal.clone(), name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ExitReason, LifecycleState, MajordomeApp};
+
+    #[tokio::test]
+    async fn test_lifecycle() {
+        let bld = MajordomeApp::builder().await;
+        let gate = bld.readiness_gate("warmup");
+        let app = bld.build().await;
+
+        assert_eq!(app.lifecycle_state(), LifecycleState::Starting);
+        assert_eq!(app.pending_readiness_gates(), vec!["warmup".to_string()]);
+
+        let ready = tokio::spawn({
+            let app = app.clone();
+            async move { app.wait_for_lifecycle_state(LifecycleState::Ready).await }
+        });
+        gate.release();
+        ready.await.unwrap();
+        assert!(app.is_ready());
+
+        let gate = app.readiness_gate("rewarm");
+        assert!(!app.is_ready());
+        drop(gate);
+        assert!(app.is_ready());
+
+        app.request_exit(ExitReason::requested("test", "done"));
+        assert_eq!(app.lifecycle_state(), LifecycleState::Draining);
+
+        let rx = app.watch_lifecycle();
+        app.clone().stop().await;
+        assert_eq!(*rx.borrow(), LifecycleState::Stopped);
+        assert!(!app.is_ready());
+    }
+}
@@ -1,7 +1,7 @@
 use super::AppMod;
 use crate::{
-    AppModInitOptions, AppModPointer, AppModRuntime, AppModTask, EnvEntry, MajordomeApp,
-    MajordomeAppInner,
+    find_unused_keys, settings::MajordomeSettings, AppModInitOptions, AppModPointer, AppModRuntime,
+    AppModTask, EnvEntry, MajordomeApp, MajordomeAppInner,
 };
 use std::{
     any::TypeId,
@@ -17,6 +17,9 @@ pub struct AppModBuilder {
     pub(crate) loaded: HashMap<String, HashMap<TypeId, HashSet<u64>>>, // name -> (typeid, ConfigHash)
     pub(crate) loaded_targets_count: usize,
     pub(crate) env_entries: Vec<EnvEntry>,
+    pub(crate) config_prefixes: Vec<String>,
+    pub(crate) strict_config: Option<bool>,
+    pub(crate) capture_panics: Option<bool>,
 }
 
 impl AppModBuilder {
@@ -84,6 +87,9 @@ impl AppModBuilder {
             }
             None => {
                 self.push_chain::<P, M::ModConfig>(&config);
+                self.app
+                    .signal
+                    .sd_notify(&format!("STATUS=Loading module {}", repr_pointer_type::<P>()));
 
                 let module = M::init(self, config.clone()).await.expect(
                     format!(
@@ -101,7 +107,7 @@ impl AppModBuilder {
                     .modules_refs
                     .lock()
                     .await
-                    .push((repr_pointer_type::<P>(), Box::new(module.clone())));
+                    .push((repr_pointer_type::<P>(), Arc::new(module.clone())));
 
                 self.loadchain.pop();
 
@@ -151,6 +157,48 @@ impl AppModBuilder {
         }
     }
 
+    pub(crate) fn register_config_prefix(&mut self, prefix: String) {
+        if !self.config_prefixes.contains(&prefix) {
+            self.config_prefixes.push(prefix);
+        }
+    }
+
+    /// Fail the build if configuration keys sharing a module prefix were not used.
+    /// Overrides `MAJORDOME_STRICT_CONFIG`.
+    pub fn strict_config(mut self, strict: bool) -> Self {
+        self.strict_config = Some(strict);
+        self
+    }
+
+    /// Install a panic hook reporting panics as internal errors, attributed to the module task.
+    /// Overrides `MAJORDOME_CAPTURE_PANICS`.
+    pub fn capture_panics(mut self, capture: bool) -> Self {
+        self.capture_panics = Some(capture);
+        self
+    }
+
+    fn check_unused_config_keys(&mut self) {
+        let unused = find_unused_keys(&self.app.config, &self.config_prefixes, &self.env_entries);
+        if unused.is_empty() {
+            return;
+        }
+
+        let strict = self
+            .strict_config
+            .unwrap_or(self.app.settings.strict_config);
+
+        for key in &unused {
+            eprintln!("⚠️ Unused configuration key {}.", key);
+        }
+
+        if strict {
+            panic!(
+                "Found {} unused configuration keys (strict mode).",
+                unused.len()
+            );
+        }
+    }
+
     fn dump_env_entries_if_requested(&self) -> Option<bool> {
         for arg in std::env::args() {
             if let Some(file_path) = arg.strip_prefix("--majordome-dump-env=") {
@@ -175,7 +223,9 @@ impl AppModBuilder {
         None
     }
 
-    pub async fn build(self) -> MajordomeApp {
+    pub async fn build(mut self) -> MajordomeApp {
+        self.app.settings = MajordomeSettings::load(&mut self);
+
         if let Some(dumped) = self.dump_env_entries_if_requested() {
             if dumped {
                 std::process::exit(0);
@@ -184,6 +234,15 @@ impl AppModBuilder {
             }
         }
 
+        self.check_unused_config_keys();
+        if self
+            .capture_panics
+            .unwrap_or(self.app.settings.capture_panics)
+        {
+            crate::panic::install_panic_hook();
+        }
+        self.app.config_store.get_mut().unwrap().values = self.app.config.clone();
+
         println!(
             "🏁 Loaded {} modules ({} pointers).",
             self.loaded_targets_count,
@@ -193,8 +252,12 @@ impl AppModBuilder {
             inner: Arc::new(self.app),
         };
         a._start_exiting_probe();
+        a._start_config_reload_probe();
+        a._start_watchdog_probe();
+        a._start_error_flush_probe();
 
         load_modules(a.clone()).await;
+        a.signal.mark_started();
         a
     }
 
@@ -259,7 +322,7 @@ async fn load_modules(app: MajordomeApp) {
     for (name, module) in app.modules.modules_refs.lock().await.iter() {
         let task = module.run(app.clone()).await;
         for task in task {
-            tasks.push(task.module_name(name));
+            tasks.push(app.watch_task(task.module_name(name)));
         }
     }
 
@@ -272,8 +335,10 @@ async fn load_modules(app: MajordomeApp) {
     println!("Loaded {} tasks.", handles.len());
 }
 
-pub(crate) async fn stop_modules(app: MajordomeApp) {
+/// Stop the modules and wait for their tasks, returns how many of them failed.
+pub(crate) async fn stop_modules(app: MajordomeApp) -> usize {
     let mut tasks = Vec::new();
+    let mut failed = 0;
 
     let mut refs = app.modules.modules_refs.lock().await;
     let mut refs_new = Vec::new();
@@ -309,6 +374,7 @@ pub(crate) async fn stop_modules(app: MajordomeApp) {
                 task.start_time.elapsed()
             ),
             Err(e) => {
+                failed += 1;
                 println!(
                     "Task {} ({}) failed to stop after {:?}: {:?}",
                     task.name,
@@ -320,7 +386,11 @@ pub(crate) async fn stop_modules(app: MajordomeApp) {
         }
     }
 
+    if failed > 0 {
+        eprintln!("⚠️ {} tasks failed while stopping.", failed);
+    }
     println!("👋 All modules stopped. Bye bye.");
+    failed
 }
 
 fn hash_config<C: Hash + 'static>(cfg: &C) -> u64 {
@@ -0,0 +1,174 @@
+use crate::module::{AppModBuilder, AppModInitOptions};
+
+mod provider;
+mod reload;
+mod report;
+mod unused;
+mod values;
+pub use provider::*;
+pub use reload::*;
+pub use report::*;
+pub(crate) use unused::find_unused_keys;
+pub use values::*;
+
+#[derive(Debug, Clone)]
+pub struct EnvEntry {
+    pub key: String,
+    pub value: String,
+}
+
+pub struct AppModConfigGetter<'a> {
+    pub ns: Option<String>,
+    pub bld: &'a mut AppModBuilder,
+    pub name: String,
+}
+
+impl<'a> AppModConfigGetter<'a> {
+    pub fn new<T>(o: &AppModInitOptions<T>, bld: &'a mut AppModBuilder, name: &str) -> Self {
+        let getter = AppModConfigGetter {
+            ns: o.ns.clone(),
+            bld,
+            name: name.to_string(),
+        };
+
+        let prefix = getter.create_key("");
+        getter.bld.register_config_prefix(prefix);
+        getter
+    }
+
+    pub fn get_or<T>(&mut self, key: &str, default: &T) -> T
+    where
+        T: Clone + std::str::FromStr + std::fmt::Display,
+    {
+        let key = self.create_key(key);
+        self.bld.register_env_entry(key.clone(), default.to_string());
+
+        let (s, source) = match self.lookup(&key) {
+            Some(v) => v,
+            None => {
+                self.record(&key, ConfigSource::Default, Some(default.to_string()));
+                return default.clone();
+            }
+        };
+
+        match s.parse::<T>() {
+            Ok(v) => {
+                self.record(&key, source, Some(s));
+                v
+            }
+            Err(_) => {
+                eprintln!("Failed to parse config value for key '{}'.", key);
+                let fallback = default.to_string();
+                self.record(&key, ConfigSource::ParseErrorFallback, Some(fallback));
+                default.clone()
+            }
+        }
+    }
+
+    pub fn get_or_panic<T>(&mut self, key: &str) -> T
+    where
+        T: Clone + std::str::FromStr,
+    {
+        let key = self.create_key(key);
+        self.bld.register_env_entry(key.clone(), "<REQUIRED>".to_string());
+
+        let (s, source) = match self.lookup(&key) {
+            Some(v) => v,
+            None => panic!("Config value for key '{}' not found.", key),
+        };
+
+        match s.parse::<T>() {
+            Ok(v) => {
+                self.record(&key, source, Some(s));
+                v
+            }
+            Err(_) => {
+                panic!("Failed to parse config value for key '{}'.", key);
+            }
+        }
+    }
+
+    pub fn get_optional<T>(&mut self, key: &str) -> Option<T>
+    where
+        T: Clone + std::str::FromStr,
+    {
+        let key = self.create_key(key);
+        self.bld.register_env_entry(key.clone(), "".to_string());
+
+        let (s, source) = match self.lookup(&key) {
+            Some(v) => v,
+            None => {
+                self.record(&key, ConfigSource::Unset, None);
+                return None;
+            }
+        };
+
+        match s.parse::<T>() {
+            Ok(v) => {
+                self.record(&key, source, Some(s));
+                Some(v)
+            }
+            Err(_) => {
+                eprintln!("Failed to parse config value for key '{}'.", key);
+                self.record(&key, ConfigSource::ParseErrorFallback, None);
+                None
+            }
+        }
+    }
+
+    /// Mark a key as secret: its value will be redacted from the config report.
+    /// Keys containing PASSWORD, SECRET, TOKEN... are considered secret by default.
+    pub fn mark_secret(&mut self, key: &str) {
+        let key = self.create_key(key);
+        self.store().secrets.insert(key);
+    }
+
+    /// Mark a key as reloadable: when it changes, modules are notified through
+    /// `AppModRuntime::on_config_change` instead of the change requiring a restart.
+    pub fn reloadable(&mut self, key: &str) {
+        let key = self.create_key(key);
+        self.store().reloadable.insert(key);
+    }
+
+    /// Full name of a key for this module, as found in the env.
+    pub fn full_key(&self, key: &str) -> String {
+        self.create_key(key)
+    }
+
+    fn store(&mut self) -> &mut ConfigStore {
+        self.bld.app.config_store.get_mut().unwrap()
+    }
+
+    fn lookup(&mut self, key: &str) -> Option<(String, ConfigSource)> {
+        let value = self.bld.app.config.get(key)?.clone();
+        let source = match self.store().sources.get(key) {
+            Some(source) => source.clone(),
+            None => ConfigSource::Env,
+        };
+        Some((value, source))
+    }
+
+    fn record(&mut self, key: &str, source: ConfigSource, value: Option<String>) {
+        let module = self.name.clone();
+        let store = self.store();
+        let secret = store.is_secret(key);
+        store.records.insert(
+            key.to_string(),
+            ConfigRecord {
+                key: key.to_string(),
+                module,
+                source,
+                value,
+                secret,
+            },
+        );
+    }
+
+    fn create_key(&self, key: &str) -> String {
+        match &self.ns {
+            Some(ns) => format!("{}_{}_{}", ns, self.name, key),
+            None => format!("{}_{}", self.name, key),
+        }
+        .to_uppercase()
+    }
+}
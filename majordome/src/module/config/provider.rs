@@ -0,0 +1,424 @@
+use std::{collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+
+use crate::{
+    app::parse_config_file, AppModBuilder, ConfigSource, ConfigStore, MajordomeApp, MajordomeError,
+};
+
+/// A source of configuration values besides the env and the configuration file,
+/// eg. a secrets service.
+/// Providers are registered on the builder, before modules are loaded,
+/// and consulted again when the configuration is reloaded.
+#[async_trait]
+pub trait ConfigProvider: Send + Sync {
+    /// Name of the provider, shown in the config report.
+    fn name(&self) -> String;
+
+    /// Whether the values of this provider must be redacted from the config report.
+    fn is_secret(&self) -> bool {
+        false
+    }
+
+    async fn load(&self) -> Result<HashMap<String, String>, MajordomeError>;
+}
+
+impl AppModBuilder {
+    /// Load configuration values from a provider.
+    /// Provider values take precedence over the configuration file, env values take precedence over providers.
+    pub async fn provider<P: ConfigProvider + 'static>(mut self, provider: P) -> Self {
+        let name = provider.name();
+        let values = match provider.load().await {
+            Ok(values) => values,
+            Err(e) => panic!(
+                "Failed to load configuration from provider '{}': {}",
+                name, e.message
+            ),
+        };
+
+        println!(
+            "✅ Loaded {} configuration entries from provider '{}'.",
+            values.len(),
+            name
+        );
+
+        let store = self.app.config_store.get_mut().unwrap();
+        overlay(&mut self.app.config, &mut store.sources, &name, &values);
+        if provider.is_secret() {
+            store.secrets.extend(values.keys().cloned());
+        }
+        store.provider_cache.insert(name, values);
+        store.providers.push(Arc::new(provider));
+        self
+    }
+}
+
+fn overlay(
+    config: &mut HashMap<String, String>,
+    sources: &mut HashMap<String, ConfigSource>,
+    name: &str,
+    values: &HashMap<String, String>,
+) {
+    for (k, v) in values {
+        if sources.get(k) == Some(&ConfigSource::Env) {
+            continue;
+        }
+
+        sources.insert(k.clone(), ConfigSource::Provider(name.to_string()));
+        config.insert(k.clone(), v.clone());
+    }
+}
+
+impl MajordomeApp {
+    /// Query the providers again, falling back to their cached values on failure.
+    pub(crate) async fn reload_providers(&self, fresh: &mut ConfigStore) {
+        let providers = self.config_store.read().unwrap().providers.clone();
+
+        for provider in providers {
+            let name = provider.name();
+            let values = match provider.load().await {
+                Ok(values) => {
+                    let mut store = self.config_store.write().unwrap();
+                    if provider.is_secret() {
+                        store.secrets.extend(values.keys().cloned());
+                    }
+                    store.provider_cache.insert(name.clone(), values.clone());
+                    values
+                }
+                Err(e) => {
+                    eprintln!(
+                        "Failed to reload configuration from provider '{}', using cached values: {}",
+                        name, e.message
+                    );
+                    let store = self.config_store.read().unwrap();
+                    store.provider_cache.get(&name).cloned().unwrap_or_default()
+                }
+            };
+
+            overlay(&mut fresh.values, &mut fresh.sources, &name, &values);
+        }
+    }
+}
+
+fn provider_error(name: &str, message: String) -> MajordomeError {
+    MajordomeError::new(
+        "errors.majordome.config_provider".to_string(),
+        format!("Configuration provider '{}' failed: {}", name, message),
+        vec![name.to_string(), message],
+        500,
+    )
+}
+
+/// Flatten a JSON object into configuration entries.
+/// Strings are kept as is, other values are serialized.
+fn json_to_entries(name: &str, body: &str) -> Result<HashMap<String, String>, MajordomeError> {
+    let value: serde_json::Value =
+        serde_json::from_str(body).map_err(|e| provider_error(name, e.to_string()))?;
+
+    let serde_json::Value::Object(object) = value else {
+        return Err(provider_error(name, "expected a JSON object".to_string()));
+    };
+
+    Ok(object
+        .into_iter()
+        .filter(|(_, v)| !v.is_null())
+        .map(|(k, v)| match v {
+            serde_json::Value::String(s) => (k, s),
+            v => (k, v.to_string()),
+        })
+        .collect())
+}
+
+/// Load configuration values from a command output.
+/// The output is either a JSON object or `KEY=VALUE` lines.
+/// ```rs
+/// MajordomeApp::builder()
+///     .await
+///     .provider(CommandProvider::new("vault-env").arg("production").secret(true))
+///     .await
+/// ```
+pub struct CommandProvider {
+    program: String,
+    args: Vec<String>,
+    secret: bool,
+}
+
+impl CommandProvider {
+    pub fn new(program: &str) -> Self {
+        CommandProvider {
+            program: program.to_string(),
+            args: Vec::new(),
+            secret: false,
+        }
+    }
+
+    pub fn arg(mut self, arg: &str) -> Self {
+        self.args.push(arg.to_string());
+        self
+    }
+
+    pub fn args<I: IntoIterator<Item = S>, S: Into<String>>(mut self, args: I) -> Self {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn secret(mut self, secret: bool) -> Self {
+        self.secret = secret;
+        self
+    }
+}
+
+#[async_trait]
+impl ConfigProvider for CommandProvider {
+    fn name(&self) -> String {
+        format!("exec:{}", self.program)
+    }
+
+    fn is_secret(&self) -> bool {
+        self.secret
+    }
+
+    async fn load(&self) -> Result<HashMap<String, String>, MajordomeError> {
+        let name = self.name();
+        let output = tokio::process::Command::new(&self.program)
+            .args(&self.args)
+            .output()
+            .await
+            .map_err(|e| provider_error(&name, e.to_string()))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(provider_error(
+                &name,
+                format!("exited with {}: {}", output.status, stderr.trim()),
+            ));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        match stdout.trim_start().starts_with('{') {
+            true => json_to_entries(&name, &stdout),
+            false => Ok(parse_config_file(&stdout).into_iter().collect()),
+        }
+    }
+}
+
+/// Load configuration values from a JSON object served over HTTP.
+/// ```rs
+/// MajordomeApp::builder()
+///     .await
+///     .provider(HttpJsonProvider::new("https://secrets.internal/v1/my-service").bearer(&token).secret(true))
+///     .await
+/// ```
+#[cfg(feature = "http")]
+pub struct HttpJsonProvider {
+    url: String,
+    headers: Vec<(String, String)>,
+    timeout: std::time::Duration,
+    secret: bool,
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "http")]
+impl HttpJsonProvider {
+    pub fn new(url: &str) -> Self {
+        HttpJsonProvider {
+            url: url.to_string(),
+            headers: Vec::new(),
+            timeout: std::time::Duration::from_secs(10),
+            secret: false,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn bearer(self, token: &str) -> Self {
+        self.header("Authorization", &format!("Bearer {}", token))
+    }
+
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn secret(mut self, secret: bool) -> Self {
+        self.secret = secret;
+        self
+    }
+}
+
+#[cfg(feature = "http")]
+#[async_trait]
+impl ConfigProvider for HttpJsonProvider {
+    fn name(&self) -> String {
+        format!("http:{}", self.url)
+    }
+
+    fn is_secret(&self) -> bool {
+        self.secret
+    }
+
+    async fn load(&self) -> Result<HashMap<String, String>, MajordomeError> {
+        let name = self.name();
+        let mut req = self.client.get(&self.url).timeout(self.timeout);
+        for (k, v) in &self.headers {
+            req = req.header(k, v);
+        }
+
+        let resp = req
+            .send()
+            .await
+            .map_err(|e| provider_error(&name, e.to_string()))?;
+
+        let status = resp.status();
+        let body = resp
+            .text()
+            .await
+            .map_err(|e| provider_error(&name, e.to_string()))?;
+
+        if !status.is_success() {
+            return Err(provider_error(&name, format!("HTTP {}", status)));
+        }
+
+        json_to_entries(&name, &body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct StaticProvider(Vec<(&'static str, &'static str)>);
+
+    #[async_trait]
+    impl ConfigProvider for StaticProvider {
+        fn name(&self) -> String {
+            "static".to_string()
+        }
+
+        fn is_secret(&self) -> bool {
+            true
+        }
+
+        async fn load(&self) -> Result<HashMap<String, String>, MajordomeError> {
+            Ok(self
+                .0
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_provider_precedence() {
+        let mut bld = MajordomeApp::builder().await;
+        bld.app
+            .config
+            .insert("PTEST_A".to_string(), "env".to_string());
+        let store = bld.app.config_store.get_mut().unwrap();
+        store
+            .sources
+            .insert("PTEST_A".to_string(), ConfigSource::Env);
+        bld.app
+            .config
+            .insert("PTEST_B".to_string(), "file".to_string());
+        let store = bld.app.config_store.get_mut().unwrap();
+        store
+            .sources
+            .insert("PTEST_B".to_string(), ConfigSource::File);
+
+        let bld = bld
+            .provider(StaticProvider(vec![
+                ("PTEST_A", "provider"),
+                ("PTEST_B", "provider"),
+                ("PTEST_C", "provider"),
+            ]))
+            .await;
+
+        assert_eq!(bld.app.config["PTEST_A"], "env");
+        assert_eq!(bld.app.config["PTEST_B"], "provider");
+        let store = bld.app.config_store.read().unwrap();
+        assert_eq!(store.sources["PTEST_A"], ConfigSource::Env);
+        assert_eq!(
+            store.sources["PTEST_B"],
+            ConfigSource::Provider("static".to_string())
+        );
+        assert_eq!(
+            store.sources["PTEST_C"],
+            ConfigSource::Provider("static".to_string())
+        );
+        assert!(store.is_secret("PTEST_C"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_provider() {
+        let json = CommandProvider::new("sh")
+            .args(["-c", r#"echo '{"A": "1", "B": 2, "C": null}'"#])
+            .load()
+            .await
+            .unwrap();
+        assert_eq!(json.len(), 2);
+        assert_eq!(json["A"], "1");
+        assert_eq!(json["B"], "2");
+
+        let lines = CommandProvider::new("sh")
+            .args([
+                "-c",
+                "printf '# comment\\nA=1\\n\\n  B = \"two\"\\nBROKEN\\nC=\\n'",
+            ])
+            .load()
+            .await
+            .unwrap();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines["B"], "two");
+        assert_eq!(lines["C"], "");
+
+        let failed = CommandProvider::new("sh")
+            .args(["-c", "exit 3"])
+            .load()
+            .await;
+        assert!(failed.is_err());
+    }
+
+    #[cfg(feature = "http")]
+    #[tokio::test]
+    async fn test_http_json_provider() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = tokio::spawn(async move {
+            let (mut sock, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0; 4096];
+            let n = sock.read(&mut buf).await.unwrap();
+            let req = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+
+            let body = r#"{"DB_PASSWORD": "hunter2", "WORKERS": 4}"#;
+            let resp = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            sock.write_all(resp.as_bytes()).await.unwrap();
+            req
+        });
+
+        let values = HttpJsonProvider::new(&format!("http://{}/config", addr))
+            .bearer("t0ken")
+            .load()
+            .await
+            .unwrap();
+
+        assert_eq!(values["DB_PASSWORD"], "hunter2");
+        assert_eq!(values["WORKERS"], "4");
+
+        let req = server.await.unwrap();
+        assert!(req.starts_with("get /config"));
+        assert!(req.contains("authorization: bearer t0ken"));
+    }
+}
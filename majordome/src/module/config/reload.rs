@@ -0,0 +1,256 @@
+use std::{collections::BTreeSet, str::FromStr, time::SystemTime};
+
+use serde::Serialize;
+
+use crate::{app::get_config_from, ConfigSource, ConfigStore, MajordomeApp};
+
+/// Outcome of a configuration reload.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ConfigReload {
+    /// Reloadable keys that changed, modules were notified.
+    pub changed: Vec<String>,
+    /// Keys read by modules that changed but are not reloadable.
+    pub requires_restart: Vec<String>,
+}
+
+impl ConfigStore {
+    /// Replace the values by the freshly loaded ones and update the records.
+    pub(crate) fn apply(&mut self, fresh: ConfigStore) -> ConfigReload {
+        let keys: BTreeSet<&String> = self.values.keys().chain(fresh.values.keys()).collect();
+        let changed: Vec<String> = keys
+            .into_iter()
+            .filter(|k| self.values.get(*k) != fresh.values.get(*k))
+            .cloned()
+            .collect();
+
+        let mut reload = ConfigReload::default();
+        for key in changed {
+            let Some(record) = self.records.get_mut(&key) else {
+                // not read by any module.
+                continue;
+            };
+
+            match fresh.values.get(&key) {
+                Some(v) => {
+                    record.value = Some(v.clone());
+                    record.source = fresh.sources[&key].clone();
+                }
+                None => {
+                    record.value = None;
+                    record.source = ConfigSource::Unset;
+                }
+            }
+
+            if self.reloadable.contains(&key) {
+                reload.changed.push(key);
+            } else {
+                reload.requires_restart.push(key);
+            }
+        }
+
+        self.values = fresh.values;
+        self.sources = fresh.sources;
+        self.file = fresh.file;
+        reload
+    }
+}
+
+impl MajordomeApp {
+    /// Current value of a configuration key, including reloaded values.
+    pub fn config_value(&self, key: &str) -> Option<String> {
+        self.config_store.read().unwrap().values.get(key).cloned()
+    }
+
+    /// Current value of a configuration key, parsed.
+    pub fn config_parse<T: FromStr>(&self, key: &str) -> Option<T> {
+        self.config_value(key)?.parse().ok()
+    }
+
+    /// Reload the configuration from the env, the configuration file and the providers.
+    /// Modules are notified of the reloadable keys that changed,
+    /// other changed keys are reported as requiring a restart.
+    pub async fn reload_config(&self) -> ConfigReload {
+        let file = self.config_store.read().unwrap().file.clone();
+        let mut fresh = get_config_from(file);
+        self.reload_providers(&mut fresh).await;
+        let reload = self.config_store.write().unwrap().apply(fresh);
+
+        for key in &reload.requires_restart {
+            eprintln!(
+                "⚠️ Configuration key '{}' changed, a restart is required to apply it.",
+                key
+            );
+        }
+
+        if !reload.changed.is_empty() {
+            println!(
+                "🔄 Reloaded configuration keys: {}.",
+                reload.changed.join(", ")
+            );
+            for (_, module) in self.modules.modules_refs.lock().await.iter() {
+                module.on_config_change(self.clone(), &reload.changed).await;
+            }
+        }
+
+        reload
+    }
+
+    /// Reload the configuration on SIGHUP, and when the configuration file changes.
+    pub(crate) fn _start_config_reload_probe(&self) {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+            let s = self.clone();
+
+            tokio::spawn(async move {
+                let mut hup = match signal(SignalKind::hangup()) {
+                    Ok(hup) => hup,
+                    Err(e) => {
+                        eprintln!("Failed to listen for SIGHUP: {}", e);
+                        return;
+                    }
+                };
+
+                loop {
+                    tokio::select! {
+                        _ = hup.recv() => {
+                            println!("🔄 SIGHUP received, reloading configuration.");
+                            s.reload_config().await;
+                        },
+                        _ = s.wait_until_closing(true) => break,
+                    }
+                }
+            });
+        }
+
+        let interval = self.settings.config_watch_interval;
+        let file = self.config_store.read().unwrap().file.clone();
+        if let (Some(file), false) = (file, interval.is_zero()) {
+            let s = self.clone();
+            let modified = |file: &str| -> Option<SystemTime> {
+                std::fs::metadata(file).ok()?.modified().ok()
+            };
+            // Taken before spawning, so a change made right after the build is not missed.
+            let mut last = modified(&file);
+
+            tokio::spawn(async move {
+                loop {
+                    s.sleep_until_closing(interval, true).await;
+                    if s.is_closing() {
+                        break;
+                    }
+
+                    let m = modified(&file);
+                    if m != last {
+                        last = m;
+                        println!("🔄 Configuration file '{}' changed, reloading.", file);
+                        s.reload_config().await;
+                    }
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{AppModConfigGetter, AppModInitOptions, ConfigRecord};
+
+    #[test]
+    fn test_apply() {
+        let mut store = ConfigStore::default();
+        for (k, v) in [("APP_RATE", "10"), ("APP_HOST", "a"), ("OTHER", "x")] {
+            store.insert(k.to_string(), v.to_string(), ConfigSource::Env);
+            store.records.insert(
+                k.to_string(),
+                ConfigRecord {
+                    key: k.to_string(),
+                    module: "app".to_string(),
+                    source: ConfigSource::Env,
+                    value: Some(v.to_string()),
+                    secret: false,
+                },
+            );
+        }
+        store.records.remove("OTHER");
+        store.reloadable.insert("APP_RATE".to_string());
+
+        let mut fresh = ConfigStore::default();
+        for (k, v) in [("APP_RATE", "20"), ("APP_HOST", "b"), ("OTHER", "y")] {
+            fresh.insert(
+                k.to_string(),
+                v.to_string(),
+                ConfigSource::Provider("static".to_string()),
+            );
+        }
+
+        let reload = store.apply(fresh);
+        assert_eq!(reload.changed, vec!["APP_RATE".to_string()]);
+        assert_eq!(reload.requires_restart, vec!["APP_HOST".to_string()]);
+        assert_eq!(store.values["APP_RATE"], "20");
+        assert_eq!(store.records["APP_RATE"].value.as_deref(), Some("20"));
+        assert_eq!(
+            store.records["APP_RATE"].source,
+            ConfigSource::Provider("static".to_string())
+        );
+
+        let reload = store.apply(ConfigStore::default());
+        assert_eq!(reload.changed, vec!["APP_RATE".to_string()]);
+        assert_eq!(store.records["APP_RATE"].source, ConfigSource::Unset);
+    }
+
+    #[tokio::test]
+    async fn test_reload_on_file_change() {
+        let path =
+            std::env::temp_dir().join(format!("majordome-reload-{}.env", std::process::id()));
+        std::fs::write(&path, "RTEST_RATE=1\n").unwrap();
+
+        let mut bld = MajordomeApp::builder().await;
+        bld.app
+            .config
+            .insert("RTEST_RATE".to_string(), "1".to_string());
+        bld.app.config.insert(
+            "MAJORDOME_CONFIG_WATCH_INTERVAL".to_string(),
+            "10ms".to_string(),
+        );
+        let store = bld.app.config_store.get_mut().unwrap();
+        store.file = Some(path.to_string_lossy().to_string());
+        store
+            .sources
+            .insert("RTEST_RATE".to_string(), ConfigSource::File);
+
+        let mut c = AppModConfigGetter::new(&AppModInitOptions::<()>::new(), &mut bld, "rtest");
+        c.reloadable("rate");
+        assert_eq!(c.get_or("rate", &0u32), 1);
+        let app = bld.build().await;
+
+        std::fs::write(&path, "RTEST_RATE=2\n").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(1))
+            .unwrap();
+
+        for _ in 0..200 {
+            if app.config_value("RTEST_RATE").as_deref() == Some("2") {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(app.config_parse::<u32>("RTEST_RATE"), Some(2));
+        let report = app.config_report();
+        let record = report
+            .entries
+            .iter()
+            .find(|e| e.key == "RTEST_RATE")
+            .unwrap();
+        assert_eq!(record.source, ConfigSource::File);
+        assert_eq!(record.value.as_deref(), Some("2"));
+    }
+}
@@ -0,0 +1,161 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+};
+
+use serde::Serialize;
+
+use crate::{ConfigProvider, MajordomeApp};
+
+/// Where a configuration value came from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSource {
+    /// Read from the process environment.
+    Env,
+    /// Read from the configuration file (see `MAJORDOME_CONFIG_FILE`).
+    File,
+    /// Loaded from the named `ConfigProvider`.
+    Provider(String),
+    /// The key was not set, the module default was used.
+    Default,
+    /// The key was set but could not be parsed, the module default was used.
+    ParseErrorFallback,
+    /// The key was not set and the module has no default.
+    Unset,
+}
+
+/// The final value of a configuration key read by a module.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigRecord {
+    pub key: String,
+    pub module: String,
+    pub source: ConfigSource,
+    pub value: Option<String>,
+    pub secret: bool,
+}
+
+/// Effective configuration of the app, secrets redacted.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigReport {
+    pub entries: Vec<ConfigRecord>,
+}
+
+#[derive(Default)]
+pub(crate) struct ConfigStore {
+    // Current raw configuration entries, updated on reload.
+    pub(crate) values: HashMap<String, String>,
+    // Origin of every raw entry.
+    pub(crate) sources: HashMap<String, ConfigSource>,
+    // Keys read by modules, with their final value.
+    pub(crate) records: BTreeMap<String, ConfigRecord>,
+    // Keys explicitly marked as secret by modules.
+    pub(crate) secrets: HashSet<String>,
+    // Keys modules can adapt to without a restart.
+    pub(crate) reloadable: HashSet<String>,
+    // Configuration file the values were loaded from, if any.
+    pub(crate) file: Option<String>,
+    // Providers registered on the builder, with their last loaded values.
+    pub(crate) providers: Vec<Arc<dyn ConfigProvider>>,
+    pub(crate) provider_cache: HashMap<String, HashMap<String, String>>,
+}
+
+const REDACTED: &str = "<redacted>";
+const SECRET_MARKERS: [&str; 7] = [
+    "PASSWORD",
+    "PASSWD",
+    "SECRET",
+    "TOKEN",
+    "API_KEY",
+    "PRIVATE_KEY",
+    "CREDENTIAL",
+];
+
+impl ConfigStore {
+    pub(crate) fn insert(&mut self, key: String, value: String, source: ConfigSource) {
+        self.sources.insert(key.clone(), source);
+        self.values.insert(key, value);
+    }
+
+    pub(crate) fn is_secret(&self, key: &str) -> bool {
+        let upper = key.to_uppercase();
+        self.secrets.contains(key) || SECRET_MARKERS.iter().any(|m| upper.contains(m))
+    }
+
+    pub(crate) fn report(&self) -> ConfigReport {
+        let entries = self
+            .records
+            .values()
+            .map(|r| {
+                let secret = r.secret || self.is_secret(&r.key);
+                ConfigRecord {
+                    value: match secret {
+                        true => r.value.as_ref().map(|_| REDACTED.to_string()),
+                        false => r.value.clone(),
+                    },
+                    secret,
+                    ..r.clone()
+                }
+            })
+            .collect();
+
+        ConfigReport { entries }
+    }
+}
+
+impl MajordomeApp {
+    /// Effective configuration read by the modules, with the source of each value.
+    /// Secret values are redacted, so the report can be exposed on an admin endpoint.
+    pub fn config_report(&self) -> ConfigReport {
+        self.config_store.read().unwrap().report()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AppModConfigGetter, AppModInitOptions};
+
+    #[tokio::test]
+    async fn test_config_report() {
+        let mut bld = MajordomeApp::builder().await;
+        for (k, v, source) in [
+            ("TEST_PORT", "8080", ConfigSource::Env),
+            ("TEST_WORKERS", "many", ConfigSource::File),
+            ("TEST_DB_PASSWORD", "hunter2", ConfigSource::Env),
+            ("TEST_SIGNING", "abcd", ConfigSource::File),
+        ] {
+            bld.app.config.insert(k.to_string(), v.to_string());
+            let store = bld.app.config_store.get_mut().unwrap();
+            store.sources.insert(k.to_string(), source);
+        }
+
+        let mut c = AppModConfigGetter::new(&AppModInitOptions::<()>::new(), &mut bld, "test");
+        c.mark_secret("signing");
+        assert_eq!(c.get_or("port", &80u16), 8080);
+        assert_eq!(c.get_or("workers", &4u32), 4);
+        assert_eq!(c.get_or("timeout", &30u32), 30);
+        assert_eq!(c.get_optional::<String>("missing"), None);
+        assert_eq!(c.get_or_panic::<String>("db_password"), "hunter2");
+        assert_eq!(c.get_or_panic::<String>("signing"), "abcd");
+
+        let app = bld.build().await;
+        assert_eq!(app.config["TEST_PORT"], "8080");
+        assert_eq!(app.config_value("TEST_PORT").as_deref(), Some("8080"));
+
+        let report = app.config_report();
+        let get = |k: &str| report.entries.iter().find(|e| e.key == k).unwrap();
+
+        assert_eq!(get("TEST_PORT").source, ConfigSource::Env);
+        assert_eq!(get("TEST_PORT").value.as_deref(), Some("8080"));
+        assert_eq!(get("TEST_WORKERS").source, ConfigSource::ParseErrorFallback);
+        assert_eq!(get("TEST_WORKERS").value.as_deref(), Some("4"));
+        assert_eq!(get("TEST_TIMEOUT").source, ConfigSource::Default);
+        assert_eq!(get("TEST_MISSING").source, ConfigSource::Unset);
+        assert_eq!(get("TEST_MISSING").value, None);
+        assert_eq!(get("TEST_DB_PASSWORD").value.as_deref(), Some(REDACTED));
+        assert_eq!(get("TEST_SIGNING").source, ConfigSource::File);
+        assert_eq!(get("TEST_SIGNING").value.as_deref(), Some(REDACTED));
+        assert_eq!(get("TEST_SIGNING").module, "test");
+    }
+}
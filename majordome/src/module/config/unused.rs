@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use crate::EnvEntry;
+
+/// A configuration key sharing a module prefix that no module read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct UnusedConfigKey {
+    pub(crate) key: String,
+    pub(crate) suggestion: Option<String>,
+}
+
+impl std::fmt::Display for UnusedConfigKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.suggestion {
+            Some(s) => write!(f, "'{}' (did you mean '{}'?)", self.key, s),
+            None => write!(f, "'{}'", self.key),
+        }
+    }
+}
+
+/// Find the keys of `config` starting with a registered module prefix
+/// that were not consumed, suggesting the closest registered key.
+pub(crate) fn find_unused_keys(
+    config: &HashMap<String, String>,
+    prefixes: &[String],
+    entries: &[EnvEntry],
+) -> Vec<UnusedConfigKey> {
+    let mut unused: Vec<UnusedConfigKey> = config
+        .keys()
+        .filter(|k| prefixes.iter().any(|p| k.starts_with(p.as_str())))
+        .filter(|k| !entries.iter().any(|e| &e.key == *k))
+        .map(|k| UnusedConfigKey {
+            key: k.clone(),
+            suggestion: closest_key(k, prefixes, entries),
+        })
+        .collect();
+
+    unused.sort_by(|a, b| a.key.cmp(&b.key));
+    unused
+}
+
+fn closest_key(key: &str, prefixes: &[String], entries: &[EnvEntry]) -> Option<String> {
+    let prefix = prefixes
+        .iter()
+        .filter(|p| key.starts_with(p.as_str()))
+        .max_by_key(|p| p.len())?;
+    let name = &key[prefix.len()..];
+    // allow roughly one typo every 4 chars of the module-specific part.
+    let max_distance = (name.len() / 4).clamp(1, 3);
+
+    entries
+        .iter()
+        .filter_map(|e| Some((e.key.strip_prefix(prefix.as_str())?, &e.key)))
+        .map(|(n, k)| (edit_distance(name, n), k))
+        .filter(|(d, _)| *d <= max_distance)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, k)| k.clone())
+}
+
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        cur[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_unused_keys() {
+        let entry = |k: &str| EnvEntry {
+            key: k.to_string(),
+            value: String::new(),
+        };
+        let entries = vec![entry("DB.SCYLLA_HOSTS"), entry("DB.SCYLLA_KEYSPACE")];
+        let prefixes = vec!["DB.SCYLLA_".to_string()];
+        let config: HashMap<String, String> = [
+            "DB.SCYLLA_HOST",
+            "DB.SCYLLA_KEYSPACE",
+            "DB.SCYLLA_TIMEOUT",
+            "DB.SCYLLA_PORT",
+            "PATH",
+        ]
+        .into_iter()
+        .map(|k| (k.to_string(), String::new()))
+        .collect();
+
+        assert_eq!(
+            find_unused_keys(&config, &prefixes, &entries),
+            vec![
+                UnusedConfigKey {
+                    key: "DB.SCYLLA_HOST".to_string(),
+                    suggestion: Some("DB.SCYLLA_HOSTS".to_string()),
+                },
+                // unrelated to HOSTS, only close to it once prefixed.
+                UnusedConfigKey {
+                    key: "DB.SCYLLA_PORT".to_string(),
+                    suggestion: None,
+                },
+                UnusedConfigKey {
+                    key: "DB.SCYLLA_TIMEOUT".to_string(),
+                    suggestion: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("HOST", "HOSTS"), 1);
+        assert_eq!(edit_distance("KEYSAPCE", "KEYSPACE"), 2);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+}
@@ -0,0 +1,393 @@
+use std::{fmt, ops::Deref, str::FromStr, time::Duration};
+
+/// Error returned when a human-formatted config value cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigValueError {
+    pub value: String,
+    pub expected: &'static str,
+}
+
+impl fmt::Display for ConfigValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid {}: '{}'", self.expected, self.value)
+    }
+}
+
+impl std::error::Error for ConfigValueError {}
+
+impl ConfigValueError {
+    fn new(value: &str, expected: &'static str) -> Self {
+        ConfigValueError {
+            value: value.to_string(),
+            expected,
+        }
+    }
+}
+
+/// A duration parsed from a human format: `500ms`, `30s`, `5m`, `1h30m`, `2d`.
+/// A bare number is read as seconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ConfigDuration(pub Duration);
+
+const DURATION_UNITS: [(&str, u128); 7] = [
+    ("d", 86_400_000_000_000),
+    ("h", 3_600_000_000_000),
+    ("m", 60_000_000_000),
+    ("s", 1_000_000_000),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+impl ConfigDuration {
+    pub const fn from_secs(secs: u64) -> Self {
+        ConfigDuration(Duration::from_secs(secs))
+    }
+
+    pub const fn from_millis(millis: u64) -> Self {
+        ConfigDuration(Duration::from_millis(millis))
+    }
+
+    pub fn as_duration(&self) -> Duration {
+        self.0
+    }
+}
+
+impl FromStr for ConfigDuration {
+    type Err = ConfigValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ConfigValueError::new(s, "duration");
+        let input = s.trim().to_lowercase();
+        if input.is_empty() {
+            return Err(err());
+        }
+
+        let mut nanos: u128 = 0;
+        let mut rest = input.as_str();
+        while !rest.is_empty() {
+            let num_len = match rest.find(|c: char| !(c.is_ascii_digit() || c == '.')) {
+                Some(len) => len,
+                // a bare number, eg. `1.5`.
+                None if rest.len() == input.len() => rest.len(),
+                None => return Err(err()),
+            };
+            let unit_len = rest[num_len..]
+                .find(|c: char| c.is_ascii_digit() || c == '.')
+                .unwrap_or(rest.len() - num_len);
+
+            let num: f64 = rest[..num_len].parse().map_err(|_| err())?;
+            let unit = rest[num_len..num_len + unit_len].trim();
+            let factor: u128 = match unit {
+                "us" | "µs" => 1_000,
+                "ms" => 1_000_000,
+                "s" | "sec" | "secs" => 1_000_000_000,
+                "" if num_len == rest.len() => 1_000_000_000,
+                "m" | "min" | "mins" => 60_000_000_000,
+                "h" | "hr" | "hrs" => 3_600_000_000_000,
+                "d" | "day" | "days" => 86_400_000_000_000,
+                "ns" => 1,
+                _ => return Err(err()),
+            };
+
+            let part = (num * factor as f64).round();
+            if !part.is_finite() || part >= u128::MAX as f64 {
+                return Err(err());
+            }
+            nanos = nanos.checked_add(part as u128).ok_or_else(err)?;
+            rest = &rest[num_len + unit_len..];
+        }
+
+        let secs = u64::try_from(nanos / 1_000_000_000).map_err(|_| err())?;
+        Ok(ConfigDuration(Duration::new(
+            secs,
+            (nanos % 1_000_000_000) as u32,
+        )))
+    }
+}
+
+impl fmt::Display for ConfigDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut nanos = self.0.as_nanos();
+        if nanos == 0 {
+            return write!(f, "0s");
+        }
+
+        for (unit, factor) in DURATION_UNITS {
+            if nanos >= factor {
+                write!(f, "{}{}", nanos / factor, unit)?;
+                nanos %= factor;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Deref for ConfigDuration {
+    type Target = Duration;
+
+    fn deref(&self) -> &Duration {
+        &self.0
+    }
+}
+
+impl From<Duration> for ConfigDuration {
+    fn from(d: Duration) -> Self {
+        ConfigDuration(d)
+    }
+}
+
+impl From<ConfigDuration> for Duration {
+    fn from(d: ConfigDuration) -> Self {
+        d.0
+    }
+}
+
+/// A size in bytes parsed from a human format: `512`, `64KB`, `1.5GB`, `512MiB`.
+/// `KB`/`MB`/`GB`/`TB` are powers of 1000, `KiB`/`MiB`/`GiB`/`TiB` powers of 1024.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ByteSize(pub u64);
+
+const BYTE_UNITS: [(&str, u64); 8] = [
+    ("TiB", 1 << 40),
+    ("TB", 1_000_000_000_000),
+    ("GiB", 1 << 30),
+    ("GB", 1_000_000_000),
+    ("MiB", 1 << 20),
+    ("MB", 1_000_000),
+    ("KiB", 1 << 10),
+    ("KB", 1_000),
+];
+
+impl ByteSize {
+    pub const fn b(n: u64) -> Self {
+        ByteSize(n)
+    }
+
+    pub const fn kib(n: u64) -> Self {
+        ByteSize(n << 10)
+    }
+
+    pub const fn mib(n: u64) -> Self {
+        ByteSize(n << 20)
+    }
+
+    pub const fn gib(n: u64) -> Self {
+        ByteSize(n << 30)
+    }
+
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+impl FromStr for ByteSize {
+    type Err = ConfigValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ConfigValueError::new(s, "byte size");
+        let input = s.trim();
+        let num_len = input
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(input.len());
+
+        let (num, unit) = input.split_at(num_len);
+        let factor = match unit.trim().to_lowercase().as_str() {
+            "" | "b" => 1,
+            "k" | "kb" => 1_000,
+            "m" | "mb" => 1_000_000,
+            "g" | "gb" => 1_000_000_000,
+            "t" | "tb" => 1_000_000_000_000,
+            "ki" | "kib" => 1 << 10,
+            "mi" | "mib" => 1 << 20,
+            "gi" | "gib" => 1 << 30,
+            "ti" | "tib" => 1 << 40,
+            _ => return Err(err()),
+        };
+
+        if let Ok(n) = num.parse::<u64>() {
+            return n.checked_mul(factor).map(ByteSize).ok_or_else(err);
+        }
+
+        let n: f64 = num.parse().map_err(|_| err())?;
+        let bytes = (n * factor as f64).round();
+        if !bytes.is_finite() || bytes < 0.0 || bytes >= u64::MAX as f64 {
+            return Err(err());
+        }
+        Ok(ByteSize(bytes as u64))
+    }
+}
+
+impl fmt::Display for ByteSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (unit, factor) in BYTE_UNITS {
+            if self.0 != 0 && self.0 / factor * factor == self.0 {
+                return write!(f, "{}{}", self.0 / factor, unit);
+            }
+        }
+        write!(f, "{}B", self.0)
+    }
+}
+
+impl From<ByteSize> for u64 {
+    fn from(b: ByteSize) -> Self {
+        b.0
+    }
+}
+
+/// A comma separated list, eg. `host1:9042, host2:9042`.
+/// Items are trimmed and empty items are skipped.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct CommaList<T>(pub Vec<T>);
+
+impl<T> CommaList<T> {
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T: FromStr> FromStr for CommaList<T> {
+    type Err = T::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<T>, _>>()
+            .map(CommaList)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for CommaList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, item) in self.0.iter().enumerate() {
+            if i != 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", item)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> Deref for CommaList<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Vec<T> {
+        &self.0
+    }
+}
+
+impl<T> From<Vec<T>> for CommaList<T> {
+    fn from(v: Vec<T>) -> Self {
+        CommaList(v)
+    }
+}
+
+impl<T> IntoIterator for CommaList<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// A lenient boolean: accepts `true/false`, `yes/no`, `on/off`, `1/0`, `y/n`, `enabled/disabled`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Bool(pub bool);
+
+impl FromStr for Bool {
+    type Err = ConfigValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "true" | "t" | "yes" | "y" | "on" | "1" | "enable" | "enabled" => Ok(Bool(true)),
+            "false" | "f" | "no" | "n" | "off" | "0" | "disable" | "disabled" => Ok(Bool(false)),
+            _ => Err(ConfigValueError::new(s, "boolean")),
+        }
+    }
+}
+
+impl fmt::Display for Bool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Deref for Bool {
+    type Target = bool;
+
+    fn deref(&self) -> &bool {
+        &self.0
+    }
+}
+
+impl From<Bool> for bool {
+    fn from(b: Bool) -> Self {
+        b.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duration() {
+        let d = |s: &str| s.parse::<ConfigDuration>().map(|d| d.0);
+        assert_eq!(d("30"), Ok(Duration::from_secs(30)));
+        assert_eq!(d("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(d("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(d("1h30m"), Ok(Duration::from_secs(5400)));
+        assert_eq!(d("1.5m"), Ok(Duration::from_secs(90)));
+        assert_eq!(d(" 2D "), Ok(Duration::from_secs(172_800)));
+        assert!(d("").is_err());
+        assert!(d("10 parsecs").is_err());
+        assert!(d("s").is_err());
+        assert_eq!(d("1.5"), Ok(Duration::from_millis(1500)));
+        assert!(d("1 2s").is_err());
+        assert!(d("1h30").is_err());
+        assert!(d("10000000000000000000000000d1d").is_err());
+
+        assert_eq!(ConfigDuration::from_secs(5400).to_string(), "1h30m");
+        assert_eq!(ConfigDuration::from_millis(1500).to_string(), "1s500ms");
+        assert_eq!(ConfigDuration::default().to_string(), "0s");
+    }
+
+    #[test]
+    fn test_byte_size() {
+        let b = |s: &str| s.parse::<ByteSize>().map(|b| b.0);
+        assert_eq!(b("512"), Ok(512));
+        assert_eq!(b("512MB"), Ok(512_000_000));
+        assert_eq!(b("512MiB"), Ok(512 << 20));
+        assert_eq!(b("1.5 GB"), Ok(1_500_000_000));
+        assert_eq!(b("64k"), Ok(64_000));
+        assert!(b("12 parsecs").is_err());
+        assert!(b("").is_err());
+        assert!(b("20000000000000000000").is_err());
+        assert!(b("99999999999999999999.5GB").is_err());
+        assert!(b("18446744073709551615.5").is_err());
+        for s in ["1e30GB", "inf", "NaN", "-0", "-1.5KB"] {
+            assert!(b(s).is_err(), "{}", s);
+        }
+
+        assert_eq!(ByteSize::mib(512).to_string(), "512MiB");
+        assert_eq!(ByteSize(64_000).to_string(), "64KB");
+        assert_eq!(ByteSize(1234).to_string(), "1234B");
+    }
+
+    #[test]
+    fn test_comma_list_and_bool() {
+        let l: CommaList<String> = "a, b,,c ".parse().unwrap();
+        assert_eq!(l.0, vec!["a", "b", "c"]);
+        assert_eq!(l.to_string(), "a,b,c");
+        assert!("1,x".parse::<CommaList<u32>>().is_err());
+        assert!("".parse::<CommaList<u32>>().unwrap().is_empty());
+
+        assert_eq!("yes".parse(), Ok(Bool(true)));
+        assert_eq!("OFF".parse(), Ok(Bool(false)));
+        assert!("maybe".parse::<Bool>().is_err());
+    }
+}
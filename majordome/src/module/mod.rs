@@ -1,6 +1,6 @@
 use std::{any::Any, fmt::Debug, hash::Hash, sync::Arc};
 
-use crate::{MajordomeApp, MajordomeError};
+use crate::{HealthStatus, MajordomeApp, MajordomeError, ShutdownPhase};
 use async_trait::async_trait;
 
 pub mod builder;
@@ -10,6 +10,8 @@ mod config;
 pub use config::*;
 
 mod store;
+mod watchdog;
+pub use watchdog::*;
 use tokio::{sync::Mutex, task::JoinHandle};
 
 use self::store::{AnyMap, AnyMapByKey};
@@ -85,6 +87,18 @@ where
     async fn stop(&self, app: MajordomeApp) {
         self.as_ref().stop(app).await
     }
+
+    async fn on_config_change(&self, app: MajordomeApp, changed_keys: &[String]) {
+        self.as_ref().on_config_change(app, changed_keys).await
+    }
+
+    async fn on_shutdown_phase(&self, app: MajordomeApp, phase: ShutdownPhase) {
+        self.as_ref().on_shutdown_phase(app, phase).await
+    }
+
+    async fn health(&self, app: MajordomeApp) -> HealthStatus {
+        self.as_ref().health(app).await
+    }
 }
 
 #[derive(Default, Hash)]
@@ -103,6 +117,18 @@ where
         Vec::new()
     }
     async fn stop(&self, _app: MajordomeApp) {}
+
+    /// Called after a config reload with the reloadable keys that changed.
+    /// Use `MajordomeApp::config_value` to read the new values.
+    async fn on_config_change(&self, _app: MajordomeApp, _changed_keys: &[String]) {}
+
+    /// Called when the app enters a shutdown phase, `stop` is called after `ShutdownPhase::Stop`.
+    async fn on_shutdown_phase(&self, _app: MajordomeApp, _phase: ShutdownPhase) {}
+
+    /// Check the module health, eg. ping a database, see `MajordomeApp::health_report`.
+    async fn health(&self, _app: MajordomeApp) -> HealthStatus {
+        HealthStatus::healthy()
+    }
 }
 
 impl<T> AppModInitOptions<T> {
@@ -128,8 +154,11 @@ pub struct AppModTask {
     pub name: String,
     pub handle: JoinHandle<()>,
     pub wait: bool, // wether or not the process must wait for this task to finish before exiting.
+    pub critical: bool, // wether or not the app must exit if this task ends while running.
     pub(crate) module_name: String,
     pub(crate) start_time: std::time::Instant,
+    // Starts a task created with `spawn` once it is watched.
+    pub(crate) start: Option<tokio::sync::oneshot::Sender<()>>,
 }
 
 impl AppModTask {
@@ -138,11 +167,29 @@ impl AppModTask {
             name: "unknown".to_string(),
             handle,
             wait: true,
+            critical: false,
             module_name: "unknown".to_string(),
             start_time: std::time::Instant::now(),
+            start: None,
         }
     }
 
+    /// Spawn a task, started once the app watches it (when returned by `AppModRuntime::run`),
+    /// so even a panic on its first poll is attributed to the module.
+    pub fn spawn<F>(future: F) -> Self
+    where
+        F: std::future::Future<Output = ()> + Send + 'static,
+    {
+        let (start, started) = tokio::sync::oneshot::channel();
+        let mut task = AppModTask::new(tokio::spawn(async move {
+            // also started when dropped without being watched.
+            let _ = started.await;
+            future.await;
+        }));
+        task.start = Some(start);
+        task
+    }
+
     pub fn name(mut self, name: &str) -> Self {
         self.name = name.to_string();
         self
@@ -153,6 +200,12 @@ impl AppModTask {
         self
     }
 
+    /// Request the exit of the app (`ExitReason::TaskFailed`) if this task ends or panics while the app is not exiting.
+    pub fn critical(mut self, critical: bool) -> Self {
+        self.critical = critical;
+        self
+    }
+
     pub(crate) fn module_name(mut self, name: &str) -> Self {
         self.module_name = name.to_string();
         self
@@ -184,10 +237,11 @@ impl MajordomeApp {
 #[derive(Default)]
 pub(crate) struct ModuleStore {
     pub(crate) modules: AnyMapByKey, // Map<Type<T>, T::Target>
-    pub(crate) modules_refs: Mutex<Vec<(String, Box<dyn AppModRuntime + Send + Sync>)>>,
+    pub(crate) modules_refs: Mutex<Vec<(String, Arc<dyn AppModRuntime + Send + Sync>)>>,
     pub(crate) modules_targets_cache: AnyMap, // Map<(Type<T::Target>, Hash<InitOptions>), T::Target>
 
     pub(crate) handles: Mutex<Vec<AppModTask>>,
+    pub(crate) task_statuses: std::sync::Mutex<Vec<TaskStatus>>,
 }
 
 #[macro_export]
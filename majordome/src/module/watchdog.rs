@@ -0,0 +1,183 @@
+use serde::Serialize;
+
+use crate::{AppModTask, ExitReason, MajordomeApp};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "state")]
+pub enum TaskState {
+    Running,
+    Completed,
+    Failed { message: String },
+    Cancelled,
+}
+
+/// Status of a task returned by `AppModRuntime::run`, see `MajordomeApp::task_statuses`.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskStatus {
+    pub module: String,
+    pub name: String,
+    pub critical: bool,
+    // Panics captured by the panic hook, see `AppModBuilder::capture_panics`.
+    pub panics: u32,
+    #[serde(flatten)]
+    pub state: TaskState,
+}
+
+impl MajordomeApp {
+    pub fn task_statuses(&self) -> Vec<TaskStatus> {
+        self.modules.task_statuses.lock().unwrap().clone()
+    }
+
+    fn set_task_state(&self, index: usize, state: TaskState) {
+        if let Some(status) = self.modules.task_statuses.lock().unwrap().get_mut(index) {
+            status.state = state;
+        }
+    }
+
+    /// Observe the end of a task: the status is updated, and a task ending
+    /// while the app is not exiting is logged, a critical one requests the exit.
+    /// The returned task ends (or panics) with the watched one.
+    pub(crate) fn watch_task(&self, mut task: AppModTask) -> AppModTask {
+        let index = {
+            let mut statuses = self.modules.task_statuses.lock().unwrap();
+            statuses.push(TaskStatus {
+                module: task.module_name.clone(),
+                name: task.name.clone(),
+                critical: task.critical,
+                panics: 0,
+                state: TaskState::Running,
+            });
+            statuses.len() - 1
+        };
+
+        let app = self.clone();
+        let (module, name, critical) = (task.module_name.clone(), task.name.clone(), task.critical);
+        let inner = task.handle;
+        let id = inner.id();
+        self.register_panic_task(id, &module, &name, index);
+        if let Some(start) = task.start.take() {
+            let _ = start.send(());
+        }
+        let guard = AbortGuard {
+            app: self.clone(),
+            index,
+            task: inner.abort_handle(),
+        };
+
+        task.handle = tokio::spawn(async move {
+            let _guard = guard;
+            let res = inner.await;
+            crate::panic::unregister_panic_task(id);
+            let state = match &res {
+                Ok(()) => TaskState::Completed,
+                Err(e) if e.is_cancelled() => TaskState::Cancelled,
+                Err(e) => TaskState::Failed {
+                    message: e.to_string(),
+                },
+            };
+            app.set_task_state(index, state.clone());
+
+            if !app.is_exiting() {
+                let message = match state {
+                    TaskState::Failed { message } => message,
+                    _ => "ended unexpectedly".to_string(),
+                };
+                eprintln!(
+                    "⚠️ Task {} ({}) ended while running: {}",
+                    name, module, message
+                );
+
+                if critical {
+                    app.request_exit(ExitReason::TaskFailed {
+                        module,
+                        task: name,
+                        message,
+                    });
+                }
+            }
+
+            // keep the outcome for stop_modules.
+            if let Err(e) = res {
+                if e.is_panic() {
+                    std::panic::resume_unwind(e.into_panic());
+                }
+            }
+        });
+
+        task
+    }
+}
+
+// Aborts the watched task with the watcher, eg. on `AppModTask::handle.abort()`.
+struct AbortGuard {
+    app: MajordomeApp,
+    index: usize,
+    task: tokio::task::AbortHandle,
+}
+
+impl Drop for AbortGuard {
+    fn drop(&mut self) {
+        if self.task.is_finished() {
+            return;
+        }
+
+        self.task.abort();
+        crate::panic::unregister_panic_task(self.task.id());
+        self.app.set_task_state(self.index, TaskState::Cancelled);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{AppModTask, ExitReason, MajordomeApp, TaskState};
+
+    #[tokio::test]
+    async fn test_critical_task() {
+        let app = MajordomeApp::new().await;
+
+        let task = AppModTask::new(tokio::spawn(async {}))
+            .name("flaky")
+            .module_name("test");
+        app.watch_task(task).handle.await.unwrap();
+        assert!(!app.is_exiting());
+        assert_eq!(app.task_statuses()[0].state, TaskState::Completed);
+
+        let task = AppModTask::new(tokio::spawn(async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            panic!("lost connection");
+        }))
+        .name("consumer")
+        .critical(true)
+        .module_name("test");
+        let res = app.watch_task(task).handle.await;
+        assert!(res.unwrap_err().is_panic());
+
+        let statuses = app.task_statuses();
+        assert!(matches!(statuses[1].state, TaskState::Failed { .. }));
+        assert!(matches!(
+            app.exit_reason(),
+            Some(ExitReason::TaskFailed { task, .. }) if task == "consumer"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_abort_watched_task() {
+        let app = MajordomeApp::new().await;
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+
+        let task = AppModTask::new(tokio::spawn(async move {
+            let _tx = tx;
+            std::future::pending::<()>().await;
+        }))
+        .name("worker")
+        .module_name("test");
+        let task = app.watch_task(task);
+        task.handle.abort();
+
+        // the sender is dropped with the watched task.
+        assert!(rx.await.is_err());
+        assert_eq!(app.task_statuses()[0].state, TaskState::Cancelled);
+    }
+}
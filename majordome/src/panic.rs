@@ -0,0 +1,160 @@
+use std::{
+    any::Any,
+    backtrace::{Backtrace, BacktraceStatus},
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex, Once, OnceLock, Weak},
+};
+
+use crate::{error::report_internal_error, MajordomeApp, MajordomeAppInner};
+
+/// A panic captured by the panic hook, see `AppModBuilder::capture_panics`.
+/// It is reported as an internal error, like the errors converted to `MajordomeError`.
+#[derive(Debug)]
+pub struct TaskPanic {
+    pub module: Option<String>,
+    pub task: Option<String>,
+    pub message: String,
+    pub location: Option<String>,
+    pub backtrace: String,
+}
+
+impl fmt::Display for TaskPanic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.task, &self.module) {
+            (Some(task), Some(module)) => write!(f, "task {} ({}) panicked", task, module)?,
+            _ => write!(f, "panicked")?,
+        }
+        if let Some(location) = &self.location {
+            write!(f, " at {}", location)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl std::error::Error for TaskPanic {}
+
+struct PanicTask {
+    module: String,
+    task: String,
+    app: Weak<MajordomeAppInner>,
+    index: usize,
+}
+
+// The panic hook is global, tasks of every app are registered here.
+fn tasks() -> &'static Mutex<HashMap<tokio::task::Id, PanicTask>> {
+    static TASKS: OnceLock<Mutex<HashMap<tokio::task::Id, PanicTask>>> = OnceLock::new();
+    TASKS.get_or_init(Default::default)
+}
+
+impl MajordomeApp {
+    /// Attribute the panics of a tokio task to a module task.
+    pub(crate) fn register_panic_task(
+        &self,
+        id: tokio::task::Id,
+        module: &str,
+        task: &str,
+        index: usize,
+    ) {
+        tasks().lock().unwrap().insert(
+            id,
+            PanicTask {
+                module: module.to_string(),
+                task: task.to_string(),
+                app: Arc::downgrade(&self.inner),
+                index,
+            },
+        );
+    }
+}
+
+pub(crate) fn unregister_panic_task(id: tokio::task::Id) {
+    tasks().lock().unwrap().remove(&id);
+}
+
+/// Install the panic hook, the previous hook is still called.
+pub(crate) fn install_panic_hook() {
+    static INSTALLED: Once = Once::new();
+
+    INSTALLED.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let mut panic = TaskPanic {
+                module: None,
+                task: None,
+                message: panic_message(info.payload()),
+                location: info.location().map(|l| l.to_string()),
+                backtrace: String::new(),
+            };
+
+            // try_lock: the panic may come from a thread holding the lock.
+            if let (Some(id), Ok(tasks)) = (tokio::task::try_id(), tasks().try_lock()) {
+                if let Some(t) = tasks.get(&id) {
+                    panic.module = Some(t.module.clone());
+                    panic.task = Some(t.task.clone());
+                    count_panic(t);
+                }
+            }
+
+            // other panics only have a backtrace when enabled, eg. with RUST_BACKTRACE.
+            let backtrace = match panic.task {
+                Some(_) => Backtrace::force_capture(),
+                None => Backtrace::capture(),
+            };
+            let backtrace = match backtrace.status() {
+                BacktraceStatus::Captured => Some(backtrace.to_string()),
+                _ => None,
+            };
+            panic.backtrace = backtrace.clone().unwrap_or_default();
+
+            report_internal_error(&panic, backtrace);
+            previous(info);
+        }));
+    });
+}
+
+fn count_panic(task: &PanicTask) {
+    let Some(app) = task.app.upgrade() else {
+        return;
+    };
+
+    if let Ok(mut statuses) = app.modules.task_statuses.try_lock() {
+        if let Some(status) = statuses.get_mut(task.index) {
+            status.panics += 1;
+        }
+    };
+}
+
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        return s.to_string();
+    }
+    if let Some(s) = payload.downcast_ref::<String>() {
+        return s.clone();
+    }
+    "unknown panic".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AppModTask, MajordomeApp, TaskState};
+
+    #[tokio::test]
+    async fn test_panic_hook() {
+        super::install_panic_hook();
+        let app = MajordomeApp::new().await;
+
+        let task = AppModTask::spawn(async {
+            panic!("boom");
+        })
+        .name("worker")
+        .module_name("test");
+        let _ = app.watch_task(task).handle.await;
+
+        let status = &app.task_statuses()[0];
+        assert_eq!(status.panics, 1);
+        assert!(
+            matches!(&status.state, TaskState::Failed { message } if message.contains("panicked"))
+        );
+    }
+}
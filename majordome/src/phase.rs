@@ -0,0 +1,248 @@
+use std::{fmt, future::Future, pin::Pin, time::Instant};
+
+use tokio::task::JoinHandle;
+
+use crate::{LifecycleState, MajordomeApp};
+
+/// Phases run in order by `MajordomeApp::stop`, once the app is exiting.
+/// - NotReady: the app should be reported as not ready.
+/// - PreStop: wait `MAJORDOME_PRE_STOP_DELAY` so load balancers deregister the app.
+/// - Drain: in-flight work is finished, hooks still running after `MAJORDOME_DRAIN_TIMEOUT` are aborted.
+/// - Stop: is_closing becomes true and the modules are stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ShutdownPhase {
+    NotReady,
+    PreStop,
+    Drain,
+    Stop,
+}
+
+impl ShutdownPhase {
+    pub const ALL: [ShutdownPhase; 4] = [
+        ShutdownPhase::NotReady,
+        ShutdownPhase::PreStop,
+        ShutdownPhase::Drain,
+        ShutdownPhase::Stop,
+    ];
+}
+
+impl fmt::Display for ShutdownPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShutdownPhase::NotReady => write!(f, "not-ready"),
+            ShutdownPhase::PreStop => write!(f, "pre-stop"),
+            ShutdownPhase::Drain => write!(f, "drain"),
+            ShutdownPhase::Stop => write!(f, "stop"),
+        }
+    }
+}
+
+type HookFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+pub(crate) struct ShutdownHook {
+    phase: ShutdownPhase,
+    name: String,
+    hook: Box<dyn FnOnce(MajordomeApp) -> HookFuture + Send>,
+}
+
+impl MajordomeApp {
+    /// Run a hook when the app enters a shutdown phase.
+    /// Modules should implement `AppModRuntime::on_shutdown_phase` instead.
+    /// ```rs
+    /// app.on_shutdown_phase(ShutdownPhase::Drain, "http", |_| async move {
+    ///     in_flight.wait_empty().await;
+    /// });
+    /// ```
+    pub fn on_shutdown_phase<F, Fut>(&self, phase: ShutdownPhase, name: &str, hook: F)
+    where
+        F: FnOnce(MajordomeApp) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.signal
+            .shutdown_hooks
+            .lock()
+            .unwrap()
+            .push(ShutdownHook {
+                phase,
+                name: name.to_string(),
+                hook: Box::new(move |app| Box::pin(hook(app))),
+            });
+    }
+
+    /// Current shutdown phase, None while the app is running.
+    pub fn shutdown_phase(&self) -> Option<ShutdownPhase> {
+        *self.signal.phase.borrow()
+    }
+
+    /// Wait until the app reaches a shutdown phase.
+    /// eg. stop accepting requests on `ShutdownPhase::Drain` instead of on exit.
+    pub async fn wait_for_shutdown_phase(&self, phase: ShutdownPhase) {
+        let mut rx = self.signal.phase.subscribe();
+        let _ = rx.wait_for(|p| *p >= Some(phase)).await;
+    }
+
+    /// Run all the shutdown phases, and log how long each of them took.
+    /// Returns how many module tasks failed.
+    pub(crate) async fn run_shutdown_phases(&self) -> usize {
+        let mut timings = Vec::new();
+        let mut failed = 0;
+
+        for phase in ShutdownPhase::ALL {
+            let start = Instant::now();
+            self.signal.phase.send_replace(Some(phase));
+            println!("🛑 Entering shutdown phase {}.", phase);
+
+            match phase {
+                ShutdownPhase::PreStop => {
+                    self.run_phase_hooks(phase).await;
+                    tokio::time::sleep(self.settings.pre_stop_delay).await;
+                }
+                ShutdownPhase::Drain => {
+                    let timeout = self.settings.drain_timeout;
+                    let mut hooks = self.spawn_phase_hooks(phase).await;
+                    if tokio::time::timeout(timeout, join_phase_hooks(phase, &mut hooks))
+                        .await
+                        .is_err()
+                    {
+                        eprintln!(
+                            "⚠️ Drain did not complete after {:?}, stopping anyway.",
+                            timeout
+                        );
+                        for (name, handle) in hooks.iter().filter(|(_, h)| !h.is_finished()) {
+                            eprintln!("Aborting shutdown hook {} ({}).", name, phase);
+                            handle.abort();
+                        }
+                    }
+                }
+                ShutdownPhase::Stop => {
+                    self.signal.is_closing.send_replace(true);
+                    self.signal.set_lifecycle(LifecycleState::Closing);
+                    self.run_phase_hooks(phase).await;
+                    failed = crate::module::builder::stop_modules(self.clone()).await;
+                }
+                ShutdownPhase::NotReady => self.run_phase_hooks(phase).await,
+            }
+
+            timings.push(format!("{} {:?}", phase, start.elapsed()));
+        }
+
+        self.signal.set_lifecycle(LifecycleState::Stopped);
+        println!("⏱️ Shutdown phases: {}.", timings.join(", "));
+        failed
+    }
+
+    async fn run_phase_hooks(&self, phase: ShutdownPhase) {
+        let mut hooks = self.spawn_phase_hooks(phase).await;
+        join_phase_hooks(phase, &mut hooks).await;
+    }
+
+    /// Start the hooks of the modules and the registered hooks, they run concurrently.
+    async fn spawn_phase_hooks(&self, phase: ShutdownPhase) -> Vec<(String, JoinHandle<()>)> {
+        let modules: Vec<_> = self.modules.modules_refs.lock().await.clone();
+        let mut handles: Vec<_> = modules
+            .into_iter()
+            .map(|(name, module)| {
+                let app = self.clone();
+                let handle =
+                    tokio::spawn(async move { module.on_shutdown_phase(app, phase).await });
+                (format!("module {}", name), handle)
+            })
+            .collect();
+
+        let hooks: Vec<ShutdownHook> = {
+            let mut all = self.signal.shutdown_hooks.lock().unwrap();
+            let (hooks, rest) = std::mem::take(&mut *all)
+                .into_iter()
+                .partition(|h| h.phase == phase);
+            *all = rest;
+            hooks
+        };
+
+        handles.extend(
+            hooks
+                .into_iter()
+                .map(|h| (h.name, tokio::spawn((h.hook)(self.clone())))),
+        );
+        handles
+    }
+}
+
+async fn join_phase_hooks(phase: ShutdownPhase, hooks: &mut [(String, JoinHandle<()>)]) {
+    for (name, handle) in hooks.iter_mut() {
+        if let Err(e) = handle.await {
+            eprintln!("Shutdown hook {} ({}) failed: {:?}", name, phase, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::{MajordomeApp, ShutdownPhase};
+
+    #[tokio::test]
+    async fn test_shutdown_phases() {
+        let app = MajordomeApp::new().await;
+        let seen = Arc::new(Mutex::new(Vec::new()));
+
+        for phase in ShutdownPhase::ALL.into_iter().rev() {
+            let seen = seen.clone();
+            app.on_shutdown_phase(phase, "test", move |app| async move {
+                assert_eq!(app.shutdown_phase(), Some(phase));
+                let closing = app.is_closing();
+                seen.lock().unwrap().push((phase, closing));
+            });
+        }
+
+        let drain = tokio::spawn({
+            let app = app.clone();
+            async move { app.wait_for_shutdown_phase(ShutdownPhase::Drain).await }
+        });
+
+        assert_eq!(app.shutdown_phase(), None);
+        app.clone().stop().await;
+        drain.await.unwrap();
+
+        assert_eq!(app.shutdown_phase(), Some(ShutdownPhase::Stop));
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                (ShutdownPhase::NotReady, false),
+                (ShutdownPhase::PreStop, false),
+                (ShutdownPhase::Drain, false),
+                (ShutdownPhase::Stop, true),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_drain_timeout_aborts_hooks() {
+        struct Dropped(Arc<Mutex<bool>>);
+        impl Drop for Dropped {
+            fn drop(&mut self) {
+                *self.0.lock().unwrap() = true;
+            }
+        }
+
+        let mut bld = MajordomeApp::builder().await;
+        bld.app
+            .config
+            .insert("MAJORDOME_DRAIN_TIMEOUT".to_string(), "50ms".to_string());
+        let app = bld.build().await;
+
+        let dropped = Arc::new(Mutex::new(false));
+        let guard = Dropped(dropped.clone());
+        app.on_shutdown_phase(ShutdownPhase::Drain, "stuck", move |_| async move {
+            let _guard = guard;
+            std::future::pending::<()>().await;
+        });
+
+        let start = std::time::Instant::now();
+        app.stop().await;
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+
+        tokio::task::yield_now().await;
+        assert!(*dropped.lock().unwrap());
+    }
+}
@@ -0,0 +1,170 @@
+use std::{
+    any::Any,
+    fmt::Debug,
+    future::Future,
+    process::ExitCode,
+    sync::{Arc, Mutex},
+};
+
+use crate::{panic::panic_message, ExitReason, MajordomeApp, ShutdownPhase};
+
+impl MajordomeApp {
+    /// Run the main future until it completes or an exit is requested, then stop the app.
+    /// If the exit is requested first, the main future is awaited during `ShutdownPhase::Drain`,
+    /// and aborted if it is still running after the drain.
+    /// An error returned by the main future fails the exit code, a panic is resumed once the app is stopped.
+    /// ```rs
+    /// #[tokio::main]
+    /// async fn main() -> ExitCode {
+    ///     let app = MajordomeApp::builder().await.add::<Db>().await.build().await;
+    ///     app.run(|app| async move {
+    ///         let listener = tokio::net::TcpListener::bind("0.0.0.0:8080").await?;
+    ///         axum::serve(listener, router(app.clone()))
+    ///             .with_graceful_shutdown(app.shutdown_token(false))
+    ///             .await
+    ///     })
+    ///     .await
+    /// }
+    /// ```
+    pub async fn run<F, Fut, E>(self, main: F) -> ExitCode
+    where
+        F: FnOnce(MajordomeApp) -> Fut,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+        E: Debug + Send + 'static,
+    {
+        let mut handle = tokio::spawn(main(self.clone()));
+        let abort = handle.abort_handle();
+        let outcome: Arc<Mutex<Option<_>>> = Arc::new(Mutex::new(None));
+
+        tokio::select! {
+            res = &mut handle => {
+                *outcome.lock().unwrap() = Some(res);
+            },
+            _ = self.wait_until_closing(false) => {
+                let outcome = outcome.clone();
+                self.on_shutdown_phase(ShutdownPhase::Drain, "main", move |_| async move {
+                    let res = handle.await;
+                    *outcome.lock().unwrap() = Some(res);
+                });
+            },
+        }
+
+        let mut panic = None;
+        if let Some(res) = outcome.lock().unwrap().take() {
+            if let Some(message) = main_failure(res, &mut panic) {
+                eprintln!("❌ Main function failed: {}", message);
+                self.request_exit(ExitReason::MainFailed { message });
+            }
+        }
+
+        let (reason, failed) = self.shutdown().await;
+
+        // the main future completed during the drain, the exit reason is already set.
+        let drained = outcome.lock().unwrap().take();
+        abort.abort();
+
+        let mut drain_failed = false;
+        if let Some(res) = drained {
+            if let Some(message) = main_failure(res, &mut panic) {
+                eprintln!("❌ Main function failed while draining: {}", message);
+                drain_failed = true;
+            }
+        }
+
+        if let Some(payload) = panic {
+            std::panic::resume_unwind(payload);
+        }
+
+        let code = match (reason.exit_code(), failed > 0 || drain_failed) {
+            (0, true) => 1,
+            (code, _) => code,
+        };
+        println!("👋 Exiting ({}) with code {}.", reason, code);
+        ExitCode::from(code as u8)
+    }
+}
+
+/// Describe why the main future failed, keeping the panic payload to resume it.
+fn main_failure<E: Debug>(
+    res: Result<Result<(), E>, tokio::task::JoinError>,
+    panic: &mut Option<Box<dyn Any + Send>>,
+) -> Option<String> {
+    match res {
+        Ok(Ok(())) => None,
+        Ok(Err(e)) => Some(format!("{:?}", e)),
+        Err(e) if e.is_panic() => {
+            let payload = e.into_panic();
+            let message = panic_message(payload.as_ref());
+            *panic = Some(payload);
+            Some(format!("panicked: {}", message))
+        }
+        // aborted after the drain.
+        Err(e) if e.is_cancelled() => None,
+        Err(e) => Some(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::ExitCode;
+
+    use crate::{ExitReason, MajordomeApp};
+
+    #[tokio::test]
+    async fn test_run() {
+        let app = MajordomeApp::new().await;
+        let code = app.run(|_| async { Ok::<_, String>(()) }).await;
+        assert_eq!(code, ExitCode::SUCCESS);
+
+        let app = MajordomeApp::new().await;
+        let code = app.clone().run(|_| async { Err("boom") }).await;
+        assert_eq!(code, ExitCode::FAILURE);
+        assert!(matches!(
+            app.exit_reason(),
+            Some(ExitReason::MainFailed { .. })
+        ));
+
+        let app = MajordomeApp::new().await;
+        let code = app
+            .clone()
+            .run(|app| async move {
+                app.request_exit(ExitReason::Signal(crate::ExitSignal::Terminate));
+                app.shutdown_token(false).await;
+                Ok::<_, String>(())
+            })
+            .await;
+        assert_eq!(code, ExitCode::SUCCESS);
+    }
+
+    #[tokio::test]
+    async fn test_run_panic() {
+        let app = MajordomeApp::new().await;
+        let res = tokio::spawn(app.clone().run(|_| async {
+            panic!("oops");
+            #[allow(unreachable_code)]
+            Ok::<_, String>(())
+        }))
+        .await;
+
+        assert!(res.unwrap_err().is_panic());
+        assert!(app.is_closing());
+    }
+
+    #[tokio::test]
+    async fn test_run_aborted_after_drain() {
+        let mut bld = MajordomeApp::builder().await;
+        bld.app
+            .config
+            .insert("MAJORDOME_DRAIN_TIMEOUT".to_string(), "10ms".to_string());
+        let app = bld.build().await;
+
+        let code = app
+            .run(|app| async move {
+                app.request_exit(ExitReason::Signal(crate::ExitSignal::Terminate));
+                std::future::pending::<()>().await;
+                Ok::<_, String>(())
+            })
+            .await;
+        assert_eq!(code, ExitCode::SUCCESS);
+    }
+}
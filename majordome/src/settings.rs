@@ -0,0 +1,51 @@
+use crate::{
+    AppModBuilder, AppModConfigGetter, AppModInitOptions, Bool, CommaList, ConfigDuration,
+    ExitSignal,
+};
+
+/// Settings of majordome itself, read from the `MAJORDOME_*` keys.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MajordomeSettings {
+    // Fail the build instead of warning when unused keys are found.
+    pub(crate) strict_config: bool,
+    // Install the panic hook, see `AppModBuilder::capture_panics`.
+    pub(crate) capture_panics: bool,
+    // How often the configuration file is checked for changes, zero to disable.
+    pub(crate) config_watch_interval: std::time::Duration,
+    // Signals starting the exit phase, a second one forces the exit.
+    pub(crate) exit_signals: Vec<ExitSignal>,
+    // Time to wait in the pre-stop phase, so load balancers deregister the app.
+    pub(crate) pre_stop_delay: std::time::Duration,
+    // Maximum time of the drain phase.
+    pub(crate) drain_timeout: std::time::Duration,
+    // Maximum time of a module health check.
+    pub(crate) health_check_timeout: std::time::Duration,
+}
+
+impl MajordomeSettings {
+    pub(crate) fn load(bld: &mut AppModBuilder) -> Self {
+        let mut c = AppModConfigGetter::new(&AppModInitOptions::<()>::new(), bld, "majordome");
+
+        // Read by the app before any module, registered here so it is documented in the env dump.
+        let _ = c.get_optional::<String>("config_file");
+
+        MajordomeSettings {
+            strict_config: c.get_or("strict_config", &Bool(false)).0,
+            capture_panics: c.get_or("capture_panics", &Bool(false)).0,
+            config_watch_interval: c
+                .get_or("config_watch_interval", &ConfigDuration::from_secs(10))
+                .0,
+            exit_signals: c
+                .get_or(
+                    "exit_signals",
+                    &CommaList(vec![ExitSignal::Interrupt, ExitSignal::Terminate]),
+                )
+                .0,
+            pre_stop_delay: c.get_or("pre_stop_delay", &ConfigDuration::from_secs(0)).0,
+            drain_timeout: c.get_or("drain_timeout", &ConfigDuration::from_secs(30)).0,
+            health_check_timeout: c
+                .get_or("health_check_timeout", &ConfigDuration::from_secs(5))
+                .0,
+        }
+    }
+}
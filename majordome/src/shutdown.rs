@@ -0,0 +1,183 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex, Weak},
+    task::{Context, Poll},
+};
+
+use tokio::sync::watch;
+
+use crate::{signal::MajordomeSignal, MajordomeApp};
+
+/// A cloneable, `'static` future completing when the app exits (or closes if `ignore_exit`),
+/// or when the token is cancelled.
+/// It does not keep the app alive, and can be given to `axum::serve(...).with_graceful_shutdown`.
+/// ```rs
+/// axum::serve(listener, router)
+///     .with_graceful_shutdown(app.shutdown_token(false))
+///     .await?;
+/// ```
+pub struct ShutdownToken {
+    signal: Arc<MajordomeSignal>,
+    ignore_exit: bool,
+    node: Arc<TokenNode>,
+    wait: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
+}
+
+struct TokenNode {
+    cancelled: watch::Sender<bool>,
+    children: Mutex<Vec<Weak<TokenNode>>>,
+}
+
+impl TokenNode {
+    fn new(cancelled: bool) -> Arc<Self> {
+        Arc::new(TokenNode {
+            cancelled: watch::channel(cancelled).0,
+            children: Mutex::new(Vec::new()),
+        })
+    }
+
+    fn cancel(&self) {
+        self.cancelled.send_replace(true);
+        let children = std::mem::take(&mut *self.children.lock().unwrap());
+        for child in children.iter().filter_map(Weak::upgrade) {
+            child.cancel();
+        }
+    }
+}
+
+impl ShutdownToken {
+    pub(crate) fn new(signal: Arc<MajordomeSignal>, ignore_exit: bool) -> Self {
+        ShutdownToken {
+            signal,
+            ignore_exit,
+            node: TokenNode::new(false),
+            wait: None,
+        }
+    }
+
+    pub fn is_exiting(&self) -> bool {
+        *self.signal.is_exiting.borrow()
+    }
+
+    pub fn is_closing(&self) -> bool {
+        *self.signal.is_closing.borrow()
+    }
+
+    /// Whether this token (or one of its parents) was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        *self.node.cancelled.borrow()
+    }
+
+    /// Whether the future is (or would be) completed.
+    pub fn is_shutdown(&self) -> bool {
+        self.is_cancelled()
+            || match self.ignore_exit {
+                true => self.is_closing(),
+                false => self.is_exiting(),
+            }
+    }
+
+    /// Complete this token, its clones and its children, the app is not affected.
+    pub fn cancel(&self) {
+        self.node.cancel();
+    }
+
+    /// Create a token completing with this one, that can be cancelled on its own.
+    pub fn child(&self) -> ShutdownToken {
+        let mut children = self.node.children.lock().unwrap();
+        let node = TokenNode::new(self.is_cancelled());
+        children.retain(|c| c.strong_count() > 0);
+        children.push(Arc::downgrade(&node));
+
+        ShutdownToken {
+            signal: self.signal.clone(),
+            ignore_exit: self.ignore_exit,
+            node: node.clone(),
+            wait: None,
+        }
+    }
+
+    fn wait(&self) -> impl Future<Output = ()> + Send + 'static {
+        let mut app_rx = match self.ignore_exit {
+            true => self.signal.is_closing.subscribe(),
+            false => self.signal.is_exiting.subscribe(),
+        };
+        let mut cancelled_rx = self.node.cancelled.subscribe();
+
+        async move {
+            tokio::select! {
+                _ = app_rx.wait_for(|v| *v) => {},
+                _ = cancelled_rx.wait_for(|v| *v) => {},
+            }
+        }
+    }
+}
+
+impl Clone for ShutdownToken {
+    fn clone(&self) -> Self {
+        ShutdownToken {
+            signal: self.signal.clone(),
+            ignore_exit: self.ignore_exit,
+            node: self.node.clone(),
+            wait: None,
+        }
+    }
+}
+
+impl Future for ShutdownToken {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        if this.wait.is_none() {
+            this.wait = Some(Box::pin(this.wait()));
+        }
+
+        this.wait.as_mut().unwrap().as_mut().poll(cx)
+    }
+}
+
+impl MajordomeApp {
+    /// Get a token completing when the app exits, or when it closes if ignore_exit.
+    /// Use ignore_exit if you are inside a Module, NEVER use it on the main app.
+    pub fn shutdown_token(&self, ignore_exit: bool) -> ShutdownToken {
+        ShutdownToken::new(self.signal.clone(), ignore_exit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{ExitReason, MajordomeApp};
+
+    #[tokio::test]
+    async fn test_shutdown_token() {
+        let app = MajordomeApp::new().await;
+        let token = app.shutdown_token(false);
+        let closing = app.shutdown_token(true);
+        let child = token.child();
+        let grandchild = child.child();
+
+        grandchild.cancel();
+        grandchild.clone().await;
+        assert!(!child.is_shutdown() && !token.is_shutdown());
+
+        child.cancel();
+        assert!(child.is_cancelled() && !token.is_cancelled());
+
+        let waiting = tokio::spawn(token.clone());
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!waiting.is_finished());
+
+        app.request_exit(ExitReason::requested("test", "done"));
+        waiting.await.unwrap();
+        token.await;
+        assert!(!closing.is_shutdown());
+
+        app.stop().await;
+        assert!(closing.is_closing());
+        closing.await;
+    }
+}
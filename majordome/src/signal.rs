@@ -1,44 +1,67 @@
-use crate::MajordomeApp;
-use std::sync::{atomic::AtomicBool, OnceLock};
-use tokio::sync::Mutex;
+use crate::{
+    phase::ShutdownHook, ExitReason, ExitSignal, LifecycleState, MajordomeApp, SdNotifier,
+    ShutdownPhase, FORCED_EXIT_CODE,
+};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicU64};
+use std::sync::Mutex;
+use tokio::sync::{mpsc, watch};
 
 /// Signal handling for the app.
 /// This is used to stop the app gracefully.
-/// When a SIGINT or SIGTERM is received, the app will begin it's EXIT process:
+/// When a SIGINT or SIGTERM is received (see `MAJORDOME_EXIT_SIGNALS`),
+/// or when `request_exit` is called, the app will begin its EXIT process:
 /// - is_exiting will return true.
 /// - sleep will return immediately.
 /// - @stop handlers will be called for all modules.
-/// Then the app will begin it's CLOSING process:
+/// - the shutdown phases are run, see `ShutdownPhase`.
+///
+/// Then the app will begin its CLOSING process (`ShutdownPhase::Stop`):
 /// - is_closing will return true.
 /// - we will wait for
 pub struct MajordomeSignal {
     // When a SIGINT or SIGTERM is received, this is set to true.
     // Use this on main app to know when to stop.
-    pub(crate) is_exiting: AtomicBool,
-    pub(crate) is_exiting_channel: (
-        Mutex<Option<tokio::sync::broadcast::Sender<()>>>,
-        tokio::sync::broadcast::Receiver<()>,
-    ),
+    pub(crate) is_exiting: watch::Sender<bool>,
 
     // When the app.stop() method is called, this is set to true.
     // Use this on modules to know when to stop.
-    pub(crate) is_closing: AtomicBool,
-    pub(crate) is_closing_channel: (
-        Mutex<Option<tokio::sync::broadcast::Sender<()>>>,
-        tokio::sync::broadcast::Receiver<()>,
-    ),
+    pub(crate) is_closing: watch::Sender<bool>,
+
+    // Why the app started exiting, set once.
+    pub(crate) exit_reason: Mutex<Option<ExitReason>>,
+
+    // Current shutdown phase, None while running.
+    pub(crate) phase: watch::Sender<Option<ShutdownPhase>>,
+
+    // Hooks registered with on_shutdown_phase, run once.
+    pub(crate) shutdown_hooks: Mutex<Vec<ShutdownHook>>,
+
+    // Lifecycle state, see `LifecycleState`.
+    pub(crate) lifecycle: watch::Sender<LifecycleState>,
+
+    // Set once the app is built, it becomes ready when no readiness gate is held.
+    pub(crate) started: AtomicBool,
+    pub(crate) readiness_gates: Mutex<BTreeMap<u64, String>>,
+    pub(crate) next_gate_id: AtomicU64,
+
+    // Set when the app is run by systemd with `Type=notify`.
+    pub(crate) systemd: Option<SdNotifier>,
 }
 
 impl MajordomeSignal {
     pub fn new() -> Self {
-        let (tx_e, rx_e) = tokio::sync::broadcast::channel(1);
-        let (tx_c, rx_c) = tokio::sync::broadcast::channel(1);
-
         MajordomeSignal {
-            is_exiting: AtomicBool::new(false),
-            is_exiting_channel: (Mutex::new(Some(tx_e)), rx_e),
-            is_closing: AtomicBool::new(false),
-            is_closing_channel: (Mutex::new(Some(tx_c)), rx_c),
+            is_exiting: watch::channel(false).0,
+            is_closing: watch::channel(false).0,
+            exit_reason: Mutex::new(None),
+            phase: watch::channel(None).0,
+            shutdown_hooks: Mutex::new(Vec::new()),
+            lifecycle: watch::channel(LifecycleState::Starting).0,
+            started: AtomicBool::new(false),
+            readiness_gates: Mutex::new(BTreeMap::new()),
+            next_gate_id: AtomicU64::new(0),
+            systemd: SdNotifier::from_env(),
         }
     }
 }
@@ -46,57 +69,77 @@ impl MajordomeSignal {
 impl MajordomeApp {
     /// exiting -> closing -> terminated
     pub fn is_exiting(&self) -> bool {
-        self.signal
-            .is_exiting
-            .load(std::sync::atomic::Ordering::SeqCst)
+        *self.signal.is_exiting.borrow()
+    }
+
+    /// Why the app started exiting, if it did.
+    pub fn exit_reason(&self) -> Option<ExitReason> {
+        self.signal.exit_reason.lock().unwrap().clone()
+    }
+
+    /// Ask the app to exit, eg. after a fatal error in a module.
+    /// This drives the same path as an exit signal: is_exiting becomes true,
+    /// and the reason is returned by `stop`.
+    /// Only the first reason is kept.
+    pub fn request_exit(&self, reason: ExitReason) {
+        self.begin_exit(reason);
     }
 
     pub(crate) fn _start_exiting_probe(&self) {
-        use tokio::signal;
         let s = self.clone();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        listen_exit_signals(&self.settings.exit_signals, tx);
 
         tokio::spawn(async move {
-            #[cfg(windows)]
-            signal::ctrl_c().await.unwrap();
+            let Some(sig) = rx.recv().await else {
+                return;
+            };
+            s.begin_exit(ExitReason::Signal(sig));
 
-            #[cfg(unix)]
-            signal::unix::signal(signal::unix::SignalKind::terminate())
-                .unwrap()
-                .recv()
-                .await;
-
-            s.signal
-                .is_exiting
-                .store(true, std::sync::atomic::Ordering::SeqCst);
+            // A second signal means we should not wait for the modules to stop.
+            if let Some(sig) = rx.recv().await {
+                eprintln!(
+                    "🛑 {} received while exiting, forcing exit (code {}).",
+                    sig, FORCED_EXIT_CODE
+                );
+                std::process::exit(FORCED_EXIT_CODE);
+            }
+        });
+    }
 
-            // We drop the sender to signal the exit.
-            // This will allow all the sleeping tasks to wake up.
-            drop(s.signal.is_exiting_channel.0.lock().await.take());
+    pub(crate) fn begin_exit(&self, reason: ExitReason) {
+        println!("🛑 Exit requested: {}.", reason);
+        self.signal
+            .exit_reason
+            .lock()
+            .unwrap()
+            .get_or_insert(reason);
 
-            println!("🛑 Exit signal received.");
-        });
+        // This will allow all the sleeping tasks to wake up.
+        self.signal.is_exiting.send_replace(true);
+        self.signal.set_lifecycle(LifecycleState::Draining);
     }
 
     pub fn is_closing(&self) -> bool {
-        self.signal
-            .is_closing
-            .load(std::sync::atomic::Ordering::SeqCst)
+        *self.signal.is_closing.borrow()
+    }
+
+    fn signal_channel(&self, ignore_exit: bool) -> watch::Receiver<bool> {
+        if ignore_exit {
+            self.signal.is_closing.subscribe()
+        } else {
+            self.signal.is_exiting.subscribe()
+        }
     }
 
     /// Sleep for a duration.
     /// Stops if the app is exiting if !ignore_exit else if the app is closing.
     /// Use ignore_exit if you are inside a Module, NEVER use it on the main app.
     pub async fn sleep_until_closing(&self, duration: std::time::Duration, ignore_exit: bool) {
-        let chan = if ignore_exit {
-            &self.signal.is_closing_channel
-        } else {
-            &self.signal.is_exiting_channel
-        };
-
-        let mut rx = chan.1.resubscribe();
+        let mut rx = self.signal_channel(ignore_exit);
         tokio::select! {
             _ = tokio::time::sleep(duration) => {},
-            _ = rx.recv() => {},
+            _ = rx.wait_for(|v| *v) => {},
         }
     }
 
@@ -104,42 +147,114 @@ impl MajordomeApp {
     /// Same as sleep_until_closing but without a duration.
     /// Use ignore_exit if you are inside a Module, NEVER use it on the main app.
     pub async fn wait_until_closing(&self, ignore_exit: bool) {
-        let chan = if ignore_exit {
-            &self.signal.is_closing_channel
-        } else {
-            &self.signal.is_exiting_channel
-        };
-
-        let mut rx = chan.1.resubscribe();
-        let _ = rx.recv().await;
+        let mut rx = self.signal_channel(ignore_exit);
+        let _ = rx.wait_for(|v| *v).await;
     }
 
     /// Wait for the app to exit/stop.
     /// Same as wait_until_closing but with a static lifetime.
-    /// Only use this if you need a static lifetime, it is not recommended.
     /// Use ignore_exit if you are inside a Module, NEVER use it on the main app.
+    #[deprecated(note = "use shutdown_token instead")]
     pub fn wait_for_shutdown_static(
         &self,
         ignore_exit: bool,
-    ) -> impl std::future::Future<Output = ()> {
-        static APP: OnceLock<(MajordomeApp, bool)> = OnceLock::new();
-        let _ = APP.set((self.clone(), ignore_exit));
+    ) -> impl std::future::Future<Output = ()> + Send + 'static {
+        self.shutdown_token(ignore_exit)
+    }
 
-        async fn wait() {
-            let (app, ignore_exit) = APP.get().unwrap();
-            app.wait_until_closing(*ignore_exit).await;
+    /// Stop the app.
+    /// Should be called at the end of the main function.
+    /// Returns why the app exited, `ExitReason::Completed` if no exit was requested.
+    pub async fn stop(self) -> ExitReason {
+        self.shutdown().await.0
+    }
+
+    /// Run the stop sequence, returns the exit reason and how many tasks failed.
+    pub(crate) async fn shutdown(&self) -> (ExitReason, usize) {
+        if !self.is_exiting() {
+            self.begin_exit(ExitReason::Completed);
         }
 
-        return wait();
+        let failed = self.run_shutdown_phases().await;
+        crate::flush_error_reporters(true);
+
+        (self.exit_reason().unwrap_or(ExitReason::Completed), failed)
     }
+}
 
-    /// Stop the app.
-    /// Should be called at the end of the main function.
-    pub async fn stop(self) {
-        self.signal
-            .is_closing
-            .store(true, std::sync::atomic::Ordering::SeqCst);
-        drop(self.signal.is_closing_channel.0.lock().await.take());
-        crate::module::builder::stop_modules(self.clone()).await;
+/// Forward the configured signals to `tx`.
+fn listen_exit_signals(signals: &[ExitSignal], tx: mpsc::UnboundedSender<ExitSignal>) {
+    #[cfg(unix)]
+    for sig in signals.iter().copied() {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let kind = match sig {
+            ExitSignal::Interrupt => SignalKind::interrupt(),
+            ExitSignal::Terminate => SignalKind::terminate(),
+            ExitSignal::Quit => SignalKind::quit(),
+        };
+
+        let mut stream = match signal(kind) {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("Failed to listen for {}: {}", sig, e);
+                continue;
+            }
+        };
+
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            while stream.recv().await.is_some() {
+                if tx.send(sig).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    #[cfg(windows)]
+    {
+        let _ = signals;
+        tokio::spawn(async move {
+            while tokio::signal::ctrl_c().await.is_ok() {
+                if tx.send(ExitSignal::Interrupt).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ExitReason, MajordomeApp};
+
+    #[tokio::test]
+    async fn test_request_exit() {
+        let app = MajordomeApp::new().await;
+        assert!(!app.is_exiting());
+
+        let waiter = tokio::spawn({
+            let app = app.clone();
+            async move { app.wait_until_closing(false).await }
+        });
+
+        app.request_exit(ExitReason::requested("test", "fatal error"));
+        app.request_exit(ExitReason::requested("other", "ignored"));
+        waiter.await.unwrap();
+        assert!(app.is_exiting());
+        assert!(!app.is_closing());
+
+        let reason = app.stop().await;
+        assert_eq!(reason, ExitReason::requested("test", "fatal error"));
+        assert_eq!(reason.exit_code(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_stop_without_exit() {
+        let app = MajordomeApp::new().await;
+        let reason = app.clone().stop().await;
+        assert_eq!(reason, ExitReason::Completed);
+        assert!(app.is_exiting() && app.is_closing());
     }
 }
@@ -0,0 +1,146 @@
+use std::time::Duration;
+
+use crate::{signal::MajordomeSignal, LifecycleState, MajordomeApp};
+
+/// Sends state changes to systemd (`Type=notify` services) over the `NOTIFY_SOCKET` datagram socket.
+/// - `STATUS=` while modules are loading.
+/// - `READY=1` once the app is ready.
+/// - `STOPPING=1` when the app starts exiting.
+/// - `WATCHDOG=1` every half `WATCHDOG_USEC`, until the app is stopped.
+#[derive(Debug, Clone)]
+pub struct SdNotifier {
+    socket: String,
+}
+
+impl SdNotifier {
+    /// Notifier for a socket path, abstract sockets start with `@`.
+    pub fn new(socket: &str) -> Self {
+        SdNotifier {
+            socket: socket.to_string(),
+        }
+    }
+
+    /// Notifier for `NOTIFY_SOCKET`, None if the app is not run by systemd.
+    pub fn from_env() -> Option<Self> {
+        match std::env::var("NOTIFY_SOCKET") {
+            Ok(socket) if !socket.is_empty() => Some(Self::new(&socket)),
+            _ => None,
+        }
+    }
+
+    /// Send a state, eg. `READY=1`, multiple states are separated by newlines.
+    #[cfg(unix)]
+    pub fn notify(&self, state: &str) -> std::io::Result<()> {
+        use std::os::unix::net::UnixDatagram;
+
+        let sock = UnixDatagram::unbound()?;
+
+        #[cfg(target_os = "linux")]
+        if let Some(name) = self.socket.strip_prefix('@') {
+            use std::os::linux::net::SocketAddrExt;
+            let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+            sock.send_to_addr(state.as_bytes(), &addr)?;
+            return Ok(());
+        }
+
+        sock.send_to(state.as_bytes(), &self.socket)?;
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    pub fn notify(&self, _state: &str) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Interval of the watchdog pings, half of `WATCHDOG_USEC`.
+fn watchdog_interval() -> Option<Duration> {
+    // the watchdog may be meant for another process, eg. a shell wrapper.
+    if let Ok(pid) = std::env::var("WATCHDOG_PID") {
+        if pid.parse::<u32>().ok() != Some(std::process::id()) {
+            return None;
+        }
+    }
+
+    let usec: u64 = std::env::var("WATCHDOG_USEC").ok()?.parse().ok()?;
+    match usec {
+        0 => None,
+        usec => Some(Duration::from_micros(usec / 2)),
+    }
+}
+
+impl MajordomeSignal {
+    pub(crate) fn sd_notify(&self, state: &str) {
+        let Some(notifier) = &self.systemd else {
+            return;
+        };
+
+        if let Err(e) = notifier.notify(state) {
+            eprintln!("Failed to notify systemd ({}): {}", state.trim(), e);
+        }
+    }
+
+    pub(crate) fn sd_notify_lifecycle(&self, state: LifecycleState) {
+        match state {
+            LifecycleState::Ready => self.sd_notify("READY=1\nSTATUS=Ready"),
+            LifecycleState::Draining => self.sd_notify("STOPPING=1\nSTATUS=Exiting"),
+            _ => {}
+        }
+    }
+}
+
+impl MajordomeApp {
+    pub(crate) fn _start_watchdog_probe(&self) {
+        if self.signal.systemd.is_none() {
+            return;
+        }
+        let Some(interval) = watchdog_interval() else {
+            return;
+        };
+
+        let s = self.clone();
+        tokio::spawn(async move {
+            loop {
+                s.signal.sd_notify("WATCHDOG=1");
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {},
+                    _ = s.wait_for_lifecycle_state(LifecycleState::Stopped) => break,
+                }
+            }
+        });
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::{os::unix::net::UnixDatagram, sync::Arc, time::Duration};
+
+    use super::SdNotifier;
+    use crate::MajordomeApp;
+
+    #[tokio::test]
+    async fn test_sd_notify() {
+        let path = std::env::temp_dir().join(format!("majordome-notify-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let sock = UnixDatagram::bind(&path).unwrap();
+        sock.set_read_timeout(Some(Duration::from_secs(1))).unwrap();
+
+        let recv = || {
+            let mut buf = [0; 256];
+            let n = sock.recv(&mut buf).unwrap();
+            String::from_utf8_lossy(&buf[..n]).to_string()
+        };
+
+        let mut bld = MajordomeApp::builder().await;
+        Arc::get_mut(&mut bld.app.signal).unwrap().systemd =
+            Some(SdNotifier::new(path.to_str().unwrap()));
+
+        let app = bld.build().await;
+        assert_eq!(recv(), "READY=1\nSTATUS=Ready");
+
+        app.stop().await;
+        assert_eq!(recv(), "STOPPING=1\nSTATUS=Exiting");
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,143 @@
+use majordome::macros::IntoMajordomeError;
+use majordome::{ErrorCatalog, MajordomeError};
+
+#[derive(Debug, IntoMajordomeError)]
+#[err(prefix = "errors.test.game.")]
+pub enum GameError {
+    #[err(code = "unknown_event", msg = "Unknown event {id}", status = 404)]
+    UnknownEvent { id: String },
+
+    #[err(
+        code = "not_enough_players",
+        msg = "Not enough players (required: {required}, actual: {actual})",
+        status = 400
+    )]
+    NotEnoughPlayers { required: u32, actual: u32 },
+
+    #[err(code = "invalid_score", msg = "Invalid score {}", status = 400)]
+    InvalidScore(i64),
+
+    #[err(
+        code = "rate_limited",
+        msg = "Too many requests, retry in {retry}s",
+        status = 429,
+        header(name = "Retry-After", value = "{retry}")
+    )]
+    RateLimited { retry: u64 },
+}
+
+// named fields used by position, as accepted by format!.
+#[derive(Debug, IntoMajordomeError)]
+pub enum LobbyError {
+    #[err(
+        code = "errors.test.lobby.left",
+        msg = "Player {0} left {lobby}",
+        status = 400
+    )]
+    Left { player: String, lobby: String },
+}
+
+// implements neither Deserialize nor FromStr.
+#[derive(Debug)]
+pub struct Frame(u32);
+
+impl std::fmt::Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "frame #{}", self.0)
+    }
+}
+
+#[derive(Debug, IntoMajordomeError)]
+#[err(prefix = "errors.test.replay.")]
+pub enum ReplayError {
+    #[err(code = "corrupted", msg = "Corrupted {frame}", status = 422)]
+    Corrupted { frame: Frame },
+
+    #[err(code = "missing", msg = "Missing replay {id}", status = 404)]
+    Missing { id: String },
+}
+
+#[test]
+fn test_derive_positional_named_fields() {
+    let err: MajordomeError = LobbyError::Left {
+        player: "bob".to_string(),
+        lobby: "main".to_string(),
+    }
+    .into();
+    assert_eq!(err.message, "Player bob left main");
+}
+
+#[test]
+fn test_derive_params() {
+    let err: MajordomeError = GameError::NotEnoughPlayers {
+        required: 3,
+        actual: 1,
+    }
+    .into();
+    assert_eq!(err.values, vec!["3", "1"]);
+    assert_eq!(
+        serde_json::to_value(&err).unwrap()["params"],
+        serde_json::json!({"required": 3, "actual": 1})
+    );
+
+    let err: MajordomeError = GameError::InvalidScore(-1).into();
+    assert!(err.params.is_empty());
+}
+
+#[test]
+fn test_derive_catalog() {
+    let definitions = GameError::error_definitions();
+    assert_eq!(definitions.len(), 4);
+    assert_eq!(definitions[1].code, "errors.test.game.not_enough_players");
+    assert_eq!(definitions[1].fields, ["required", "actual"]);
+    assert_eq!(definitions[2].fields, ["0"]);
+}
+
+#[test]
+fn test_derive_headers() {
+    let err: MajordomeError = GameError::RateLimited { retry: 30 }.into();
+    assert_eq!(err.message, "Too many requests, retry in 30s");
+    assert_eq!(
+        *err.headers,
+        vec![("Retry-After".to_string(), "30".to_string())]
+    );
+}
+
+#[test]
+fn test_derive_try_from() {
+    let body = r#"{"error":"errors.test.game.not_enough_players","message":"Not enough players","values":["3","1"],"params":{"required":3,"actual":1}}"#;
+    let err = MajordomeError::from_response_body(400, body.as_bytes());
+    assert!(matches!(
+        GameError::try_from(err),
+        Ok(GameError::NotEnoughPlayers {
+            required: 3,
+            actual: 1
+        })
+    ));
+
+    let err: MajordomeError = GameError::InvalidScore(-1).into();
+    assert!(matches!(
+        GameError::try_from(err),
+        Ok(GameError::InvalidScore(-1))
+    ));
+
+    let err: MajordomeError = GameError::UnknownEvent { id: "e1".into() }.into();
+    let body = serde_json::to_vec(&err).unwrap();
+    let err = MajordomeError::from_response_body(404, &body);
+    assert!(matches!(GameError::try_from(err), Ok(GameError::UnknownEvent { id }) if id == "e1"));
+
+    let err = MajordomeError::from_response_body(502, b"Bad Gateway");
+    assert_eq!(GameError::try_from(err).unwrap_err().status_code, 502);
+}
+
+#[test]
+fn test_derive_try_from_unreadable_field() {
+    // the variant can't be read back, even from its own error.
+    let err: MajordomeError = ReplayError::Corrupted { frame: Frame(7) }.into();
+    let err = ReplayError::try_from(err).unwrap_err();
+    assert_eq!(err.error, "errors.test.replay.corrupted");
+    assert_eq!(err.message, "Corrupted frame #7");
+
+    let err: MajordomeError = ReplayError::Missing { id: "r1".into() }.into();
+    assert!(matches!(ReplayError::try_from(err), Ok(ReplayError::Missing { id }) if id == "r1"));
+}